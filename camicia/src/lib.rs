@@ -1,8 +1,8 @@
 use std::fmt::{Display, Debug, Formatter};
-use std::collections::VecDeque;
 use core::*;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub turn: Turn,
     pub pile: Deck,
@@ -13,6 +13,7 @@ pub struct Game {
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Turn {
     #[default]
     First,
@@ -22,12 +23,19 @@ pub enum Turn {
 
 impl Game {
     pub fn new() -> Game {
-        let mut first = Card::shuffled_deck().0;
-        let second = first.split_off(first.len()/2);
+        Game::from_deck(Card::shuffled_deck())
+    }
+    /// Same seed, same deal (and since camicia has no choices, the same whole game)
+    pub fn from_seed(seed: u64) -> Game {
+        Game::from_deck(Deck::shuffled_from_seed(seed))
+    }
+    /// Splits the given deck in half, one half per player
+    pub fn from_deck(deck: Deck) -> Game {
+        let (player_first, player_second) = deck.split_in_half();
         Game {
-            pile: Deck(VecDeque::new()),
-            player_first: Deck(first),
-            player_second: Deck(second),
+            pile: Deck::default(),
+            player_first,
+            player_second,
             turn: Turn::First,
             forced_move: None,
         }
@@ -121,6 +129,10 @@ Forced move?: {}
     }
 }
 
+impl Default for Game {
+    fn default() -> Self { Self::new() }
+}
+
 impl std::ops::Not for Turn {
     type Output = Self;
 
@@ -1,7 +1,12 @@
 use camicia::*;
 
+#[allow(unused_variables, unused_mut)] // `buffer` and `winner` are for the prints commented out below
 fn main() {
-    let mut game = Game::new();
+    // Optional seed as the first argument, to get the same game every time
+    let mut game = match std::env::args().nth(1).and_then(|s| s.parse().ok()) {
+        Some(seed) => Game::from_seed(seed),
+        None       => Game::new(),
+    };
 
     let mut buffer = String::new();
     loop {
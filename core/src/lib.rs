@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter};
 use std::fmt::Debug;
 use std::collections::VecDeque;
+use std::str::FromStr;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Card {
@@ -9,6 +12,7 @@ pub struct Card {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Denari,
     Coppe,
@@ -17,6 +21,7 @@ pub enum Suit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CardNum {
     Numeric(usize),
     Fante,
@@ -31,6 +36,7 @@ pub enum CardNum {
 // | Front    | Bot |
 // | Back     | Top |
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Deck(pub VecDeque<Card>);
 
 impl Deck {
@@ -55,11 +61,136 @@ impl Deck {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
     pub fn move_all_cards_to(&mut self, dest: &mut Deck) {
         for card in self.0.drain(..) {
             dest.push_to_bottom(card)
         }
     }
+
+    /// Full 40 card deck, in order (suit by suit, ace to re, the re di spade on top)
+    pub fn standard_40() -> Deck {
+        let numbers = [CardNum::Numeric(1), CardNum::Numeric(2), CardNum::Numeric(3),
+                       CardNum::Numeric(4), CardNum::Numeric(5), CardNum::Numeric(6),
+                       CardNum::Numeric(7), CardNum::Fante, CardNum::Cavallo, CardNum::Re];
+
+        let suits = [Suit::Denari, Suit::Coppe, Suit::Bastoni, Suit::Spade];
+
+        let mut deck = VecDeque::with_capacity(numbers.len()*suits.len());
+        for suit in suits {
+            for number in numbers {
+                deck.push_back(Card { number, suit  } )
+            }
+        }
+        Deck(deck)
+    }
+
+    /// Full 40 card deck, shuffled with whatever rng you hand it
+    pub fn shuffled_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Deck {
+        let mut deck = Deck::standard_40();
+        deck.shuffle(rng);
+        deck
+    }
+
+    /// Same seed, same deck. Handy for bug reports, tests and replaying a deal. ChaCha8
+    /// rather than `StdRng`, which is allowed to change between rand versions
+    pub fn shuffled_from_seed(seed: u64) -> Deck {
+        Deck::shuffled_with_rng(&mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Perfectly uniform shuffle (Fisher-Yates my beloved)
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for i in (1..self.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            self.0.swap(i, j);
+        }
+    }
+
+    /// Riffle shuffle as a human does it (Gilbert-Shannon-Reeds model): cut roughly in
+    /// half, then let cards fall from either half with odds proportional to its size.
+    /// One riffle is far from random, around seven of them is good enough
+    pub fn riffle_shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let cut = (0..self.len()).filter(|_| rng.gen_bool(0.5)).count();
+        let mut top_half = self.0.split_off(cut);
+        let mut bottom_half = std::mem::take(&mut self.0);
+
+        while !bottom_half.is_empty() || !top_half.is_empty() {
+            let (b, t) = (bottom_half.len(), top_half.len());
+            let from_bottom = rng.gen_range(0..b + t) < b;
+            let card = if from_bottom { bottom_half.pop_front() } else { top_half.pop_front() };
+            self.0.extend(card);
+        }
+    }
+
+    /// Overhand shuffle: small packets are slid off the top and piled up in a new stack,
+    /// which reverses the order of the packets (but not the cards within them)
+    pub fn overhand_shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mut shuffled = VecDeque::with_capacity(self.len());
+        while !self.is_empty() {
+            let packet = rng.gen_range(1..=self.len().min(5));
+            let mut cards = self.0.split_off(self.len() - packet);
+            shuffled.append(&mut cards);
+        }
+        self.0 = shuffled;
+    }
+
+    /// Cut the deck: the `at` cards on top go to the bottom, keeping their order.
+    /// Cutting at more cards than there are is the same as cutting at all of them (a no-op)
+    pub fn cut(&mut self, at: usize) {
+        self.0.rotate_right(at.min(self.len()));
+    }
+
+    /// Splits the deck in two: `(bottom half, top half)`. With an odd number of cards,
+    /// the top half gets the extra one
+    pub fn split_in_half(mut self) -> (Deck, Deck) {
+        let top = self.0.split_off(self.len() / 2);
+        (self, Deck(top))
+    }
+
+    /// Takes up to `n` cards from the top, first element is the card that was on top
+    pub fn draw(&mut self, n: usize) -> Vec<Card> {
+        (0..n).map_while(|_| self.take_from_top()).collect()
+    }
+
+    /// Looks at up to `n` cards from the top without taking them, top card first
+    pub fn peek(&self, n: usize) -> impl Iterator<Item = &Card> {
+        self.0.iter().rev().take(n)
+    }
+
+    /// Deals `per_hand` cards to each of `hands` hands, one card at a time going around
+    /// the table (like a person would). Stops early if the deck runs out
+    pub fn deal(&mut self, per_hand: usize, hands: usize) -> Vec<Vec<Card>> {
+        let mut dealt = vec![Vec::with_capacity(per_hand); hands];
+        'dealing: for _ in 0..per_hand {
+            for hand in dealt.iter_mut() {
+                match self.take_from_top() {
+                    Some(c) => hand.push(c),
+                    None    => break 'dealing,
+                }
+            }
+        }
+        dealt
+    }
+
+    /// How deep a card is: 0 means it's the top card
+    pub fn find(&self, card: &Card) -> Option<usize> {
+        self.0.iter().rev().position(|c| c == card)
+    }
+
+    /// Pulls a specific card out of the deck, wherever it is
+    pub fn remove_card(&mut self, card: &Card) -> Option<Card> {
+        let i = self.0.iter().position(|c| c == card)?;
+        self.0.remove(i)
+    }
+}
+
+impl FromIterator<Card> for Deck {
+    /// First card is the bottom one, last card ends on top
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Deck {
+        Deck(iter.into_iter().collect())
+    }
 }
 
 impl Card {
@@ -93,32 +224,81 @@ impl Card {
     }
 
     pub fn shuffled_deck() -> Deck {
-        let numbers = [CardNum::Numeric(1), CardNum::Numeric(2), CardNum::Numeric(3),
-                       CardNum::Numeric(4), CardNum::Numeric(5), CardNum::Numeric(6),
-                       CardNum::Numeric(7), CardNum::Fante, CardNum::Cavallo, CardNum::Re];
+        Deck::shuffled_with_rng(&mut rand::thread_rng())
+    }
 
-        let suits = [Suit::Denari, Suit::Coppe, Suit::Bastoni, Suit::Spade];
+    /// Short ascii name: rank (`A`, `2`..`7`, `F`, `C`, `R`) followed by suit (`D`, `C`, `B`, `S`).
+    /// So `7D` is the sette bello and `CC` the cavallo di coppe. [`Card::from_str`] reads it back
+    pub fn code(&self) -> String {
+        let rank = match self.number {
+            CardNum::Numeric(1) => "A".to_string(),
+            CardNum::Numeric(n) => n.to_string(),
+            CardNum::Fante      => "F".to_string(),
+            CardNum::Cavallo    => "C".to_string(),
+            CardNum::Re         => "R".to_string(),
+        };
+        let suit = match self.suit {
+            Suit::Denari  => 'D',
+            Suit::Coppe   => 'C',
+            Suit::Bastoni => 'B',
+            Suit::Spade   => 'S',
+        };
+        format!("{rank}{suit}")
+    }
+}
 
-        let mut deck = VecDeque::with_capacity(numbers.len()*suits.len());
-        for number in numbers {
-            for suit in suits {
-                deck.push_back(Card { number, suit  } )
-            }
-        }
+/// The string wasn't a card code (see [`Card::code`])
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
 
-        // Shuffle the deck (Fisher-Yates my beloved)
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "'{}' is not a card (try something like '7D', 'AC' or 'RS')", self.0)
+    }
+}
+impl std::error::Error for ParseCardError {}
 
-        for i in (1..deck.len()).rev() {
-            let j = rng.gen_range(0..=i);
-            deck.swap(i, j);
-        }
+impl FromStr for Card {
+    type Err = ParseCardError;
 
-        Deck(deck)
+    /// Case insensitive, and ranks can also be written as numbers (`1D`, `10S`...)
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let err = || ParseCardError(s.to_string());
+        let upper = s.trim().to_ascii_uppercase();
+        if !upper.is_ascii() || upper.len() < 2 { return Err(err()) }
+
+        let (rank, suit) = upper.split_at(upper.len() - 1);
+        let suit = match suit {
+            "D" => Suit::Denari,
+            "C" => Suit::Coppe,
+            "B" => Suit::Bastoni,
+            "S" => Suit::Spade,
+            _   => return Err(err()),
+        };
+        let n = match rank {
+            "A" => 1,
+            "F" => 8,
+            "C" => 9,
+            "R" => 10,
+            n   => n.parse().ok().filter(|n| (1..=10).contains(n)).ok_or_else(err)?,
+        };
+        Ok(Card::new(suit, n))
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Card, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
 impl Display for CardNum {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
@@ -161,3 +341,92 @@ impl std::ops::Deref for Deck {
 impl std::ops::DerefMut for Deck {
     fn deref_mut(&mut self) -> &mut VecDeque<Card> { &mut self.0 }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(n: usize) -> Card { Card::denari(n) }
+
+    /// Bottom card first, like `Deck::from_iter`
+    fn cards(deck: &Deck) -> Vec<Card> {
+        deck.iter().copied().collect()
+    }
+
+    /// Every card of the standard deck is still there, once
+    fn assert_same_40(deck: &Deck) {
+        assert_eq!(deck.len(), 40);
+        for card in Deck::standard_40().iter() {
+            assert_eq!(deck.iter().filter(|c| *c == card).count(), 1, "{card} went missing");
+        }
+    }
+
+    #[test]
+    fn top_card_comes_first() {
+        let mut deck: Deck = (1..=5).map(d).collect();
+        assert_eq!(deck.top(), Some(&d(5)));
+        assert_eq!(deck.bottom(), Some(&d(1)));
+        assert_eq!(deck.peek(2).copied().collect::<Vec<_>>(), [d(5), d(4)]);
+        assert_eq!(deck.peek(10).count(), 5);
+        assert_eq!(deck.find(&d(5)), Some(0));
+        assert_eq!(deck.find(&d(1)), Some(4));
+        assert_eq!(deck.find(&Card::new(Suit::Spade, 1)), None);
+
+        assert_eq!(deck.draw(2), [d(5), d(4)]);
+        assert_eq!(deck.draw(10), [d(3), d(2), d(1)]);
+        assert!(deck.draw(1).is_empty());
+    }
+
+    #[test]
+    fn cut_moves_the_top_to_the_bottom() {
+        let mut deck: Deck = (1..=5).map(d).collect();
+        deck.cut(2);
+        assert_eq!(cards(&deck), [d(4), d(5), d(1), d(2), d(3)]);
+
+        deck.cut(0);
+        assert_eq!(cards(&deck), [d(4), d(5), d(1), d(2), d(3)]);
+        deck.cut(50);
+        assert_eq!(cards(&deck), [d(4), d(5), d(1), d(2), d(3)]);
+    }
+
+    #[test]
+    fn split_in_half_gives_the_top_the_odd_card() {
+        let (bottom, top) = (1..=5).map(d).collect::<Deck>().split_in_half();
+        assert_eq!(cards(&bottom), [d(1), d(2)]);
+        assert_eq!(cards(&top), [d(3), d(4), d(5)]);
+    }
+
+    #[test]
+    fn shuffles_keep_the_same_40_cards() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut deck = Deck::standard_40();
+        deck.riffle_shuffle(&mut rng);
+        assert_same_40(&deck);
+        deck.overhand_shuffle(&mut rng);
+        assert_same_40(&deck);
+        deck.shuffle(&mut rng);
+        assert_same_40(&deck);
+        assert_same_40(&Deck::shuffled_from_seed(7));
+        assert_eq!(cards(&Deck::shuffled_from_seed(7)), cards(&Deck::shuffled_from_seed(7)));
+    }
+
+    #[test]
+    fn deal_goes_around_the_table() {
+        let mut deck: Deck = (1..=7).map(d).collect();
+        assert_eq!(deck.deal(2, 3), [vec![d(7), d(4)], vec![d(6), d(3)], vec![d(5), d(2)]]);
+        assert_eq!(cards(&deck), [d(1)]);
+
+        // Runs out halfway through the second time around
+        let mut deck: Deck = (1..=5).map(d).collect();
+        assert_eq!(deck.deal(2, 3), [vec![d(5), d(2)], vec![d(4), d(1)], vec![d(3)]]);
+        assert!(deck.is_empty());
+    }
+
+    #[test]
+    fn remove_card_pulls_it_from_anywhere() {
+        let mut deck: Deck = (1..=5).map(d).collect();
+        assert_eq!(deck.remove_card(&d(3)), Some(d(3)));
+        assert_eq!(deck.remove_card(&d(3)), None);
+        assert_eq!(cards(&deck), [d(1), d(2), d(4), d(5)]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Bare bones client for poking at `scopa-web` by hand or from a script: every line of
+//! stdin is a request, every event from the server gets printed as its json
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio_tungstenite::tungstenite::Message;
+
+#[path = "../args.rs"]
+mod args;
+use args::arg_value;
+
+const HELP: &str = "\
+Commands:
+  rooms                          list the rooms (GET /rooms)
+  create <name> [rules]          open a room and sit in it
+  create <name> <rules> <seed>   same, with the deals picked (if the server allows seeds)
+  join <code>                    take a free seat of a room
+  spectate <name>                watch a room
+  reconnect <token>              get your seat back
+  wait <ms>                      do nothing for a while (for scripts)
+  {...}                          send raw json
+  anything else                  is sent as a move";
+
+// Not `#[tokio::main]`: it expands to `::core::future`, which isn't there in this workspace
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().expect("Could not start the tokio runtime");
+    if let Err(e) = runtime.block_on(run()) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let address = arg_value("--server").unwrap_or("127.0.0.1:8080".to_string());
+    let (socket, _) = tokio_tungstenite::connect_async(format!("ws://{address}/ws")).await?;
+    let (mut sink, mut stream) = socket.split();
+    eprintln!("Connected to {address}\n{HELP}");
+
+    let printer = tokio::spawn(async move {
+        while let Some(Ok(msg)) = stream.next().await {
+            if let Message::Text(text) = msg {
+                println!("< {}", text.as_str());
+            }
+        }
+        eprintln!("The server closed the connection");
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let request = match words.as_slice() {
+            []                     => continue,
+            ["rooms"]              => { println!("< {}", list_rooms(&address).await?); continue },
+            ["wait", ms]           => { tokio::time::sleep(Duration::from_millis(ms.parse()?)).await; continue },
+            ["create", name, rest @ ..] => serde_json::json!({
+                "type": "create",
+                "name": name,
+                "rules": rest.first(),
+                "seed": rest.get(1).map(|s| s.parse::<u64>()).transpose()?,
+            }).to_string(),
+            ["join", code]         => serde_json::json!({ "type": "join", "code": code }).to_string(),
+            ["spectate", name]     => serde_json::json!({ "type": "spectate", "room": name }).to_string(),
+            ["reconnect", token]   => serde_json::json!({ "type": "reconnect", "token": token }).to_string(),
+            _ if line.trim_start().starts_with('{') => line.clone(),
+            _                      => serde_json::json!({ "type": "move", "input": line.trim() }).to_string(),
+        };
+        sink.send(Message::Text(request.into())).await?;
+    }
+
+    sink.close().await?;
+    let _ = printer.await;
+    Ok(())
+}
+
+/// `GET /rooms`, by hand: it's one request, not worth an http client
+async fn list_rooms(address: &str) -> std::io::Result<String> {
+    let mut stream = tokio::net::TcpStream::connect(address).await?;
+    stream.write_all(format!("GET /rooms HTTP/1.0\r\nHost: {address}\r\n\r\n").as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response.split_once("\r\n\r\n").map(|(_, body)| body.to_string()).unwrap_or(response))
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use scopa::Rules;
+use crate::messages::{ClientRequest, RoomInfo, ServerEvent};
+use crate::room::{Outbox, Room};
+
+/// Join codes leave out the letters and digits that are easy to mix up (O/0, I/1)
+const CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LEN: usize = 6;
+
+/// Every room on the server, by name
+#[derive(Debug, Default)]
+pub struct Lobby {
+    rooms: HashMap<String, Room>,
+    allow_seeded_rooms: bool,
+}
+
+/// Where a connection is
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Presence {
+    #[default]
+    Nowhere,
+    Player { room: String, seat: usize },
+    Spectator { room: String },
+}
+
+impl Lobby {
+    /// Whoever picks the seed knows every hand, so rooms only get one if the server says so
+    pub fn new(allow_seeded_rooms: bool) -> Lobby {
+        Lobby { allow_seeded_rooms, ..Lobby::default() }
+    }
+
+    pub fn rooms(&self) -> Vec<RoomInfo> {
+        let mut rooms: Vec<RoomInfo> = self.rooms.values().map(Room::info).collect();
+        rooms.sort_by(|a, b| a.name.cmp(&b.name));
+        rooms
+    }
+
+    /// Does what `request` asks for the connection at `presence` (which gets updated), and
+    /// tells it how it went through `outbox`
+    pub fn handle(&mut self, presence: &mut Presence, request: ClientRequest, outbox: &Outbox) {
+        if let Err(message) = self.try_handle(presence, request, outbox) {
+            let _ = outbox.send(ServerEvent::Error { message });
+        }
+    }
+
+    fn try_handle(&mut self, presence: &mut Presence, request: ClientRequest, outbox: &Outbox) -> Result<(), String> {
+        let in_room = *presence != Presence::Nowhere;
+        match request {
+            ClientRequest::Move { input } => match presence {
+                Presence::Player { room, seat } => {
+                    let room = self.rooms.get_mut(room).ok_or("your room is gone")?;
+                    if !room.holds(*seat, outbox) {
+                        *presence = Presence::Nowhere;
+                        return Err("your seat was taken back from another connection".to_string());
+                    }
+                    room.play(*seat, &input);
+                },
+                Presence::Spectator { .. } => return Err("spectators can't play".to_string()),
+                Presence::Nowhere          => return Err("create or join a room first".to_string()),
+            },
+            _ if in_room => return Err("you're already in a room".to_string()),
+
+            ClientRequest::Create { name, rules, seed } => {
+                if seed.is_some() && !self.allow_seeded_rooms {
+                    return Err("this server doesn't take seeds: whoever picks the deals would know every hand".to_string());
+                }
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return Err("rooms need a name".to_string());
+                }
+                if self.rooms.contains_key(&name) {
+                    return Err(format!("there's already a room called '{name}'"));
+                }
+                let rules = match rules {
+                    None         => Rules::default(),
+                    Some(preset) => Rules::preset(&preset)
+                        .ok_or(format!("unknown rules '{preset}': pick one of {}", Rules::PRESETS.join(", ")))?,
+                };
+
+                let mut room = Room::new(name.clone(), self.new_code(), rules, seed);
+                let seat = room.sit(self.new_token(), outbox.clone()).expect("A new room has free seats");
+                self.rooms.insert(name.clone(), room);
+                *presence = Presence::Player { room: name, seat };
+            },
+            ClientRequest::Join { code } => {
+                let code = code.trim().to_ascii_uppercase();
+                let token = self.new_token();
+                let room = self.rooms.values_mut().find(|r| r.code == code)
+                    .ok_or(format!("no room has the code '{code}'"))?;
+                let seat = room.sit(token, outbox.clone()).ok_or("that room is full, you can spectate it instead")?;
+                *presence = Presence::Player { room: room.name.clone(), seat };
+            },
+            ClientRequest::Spectate { room: name } => {
+                let room = self.rooms.get_mut(&name).ok_or(format!("there's no room called '{name}'"))?;
+                room.spectate(outbox.clone());
+                *presence = Presence::Spectator { room: name };
+            },
+            ClientRequest::Reconnect { token } => {
+                let room = self.rooms.values_mut().find(|r| r.has_token(&token))
+                    .ok_or("that token doesn't belong to any seat (the game might be over)")?;
+                let seat = room.reconnect(&token, outbox.clone()).expect("The room has a seat with this token");
+                *presence = Presence::Player { room: room.name.clone(), seat };
+            },
+        }
+        Ok(())
+    }
+
+    /// The connection at `presence` (talking through `outbox`) is gone. Rooms nobody's left
+    /// in get closed once they can't be played anymore (finished, or still waiting for players)
+    pub fn disconnect(&mut self, presence: &Presence, outbox: &Outbox) {
+        let name = match presence {
+            Presence::Nowhere               => return,
+            Presence::Player { room, seat } => {
+                if let Some(r) = self.rooms.get_mut(room) { r.leave(*seat, outbox) }
+                room
+            },
+            Presence::Spectator { room }    => {
+                if let Some(r) = self.rooms.get_mut(room) { r.spectators.retain(|s| !s.is_closed()) }
+                room
+            },
+        };
+        if self.rooms.get(name).is_some_and(|r| r.is_empty() && (r.over || !r.is_full())) {
+            self.rooms.remove(name);
+        }
+    }
+
+    fn new_code(&self) -> String {
+        let mut rng = rand::thread_rng();
+        loop {
+            let code: String = (0..CODE_LEN).map(|_| CODE_CHARS[rng.gen_range(0..CODE_CHARS.len())] as char).collect();
+            if self.rooms.values().all(|r| r.code != code) {
+                return code;
+            }
+        }
+    }
+
+    fn new_token(&self) -> String {
+        format!("{:032x}", rand::thread_rng().gen::<u128>())
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    response::IntoResponse,
+    routing::get,
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+
+mod args;
+mod lobby;
+mod messages;
+mod room;
+use crate::args::*;
+use crate::lobby::*;
+use crate::messages::*;
+
+type Shared = Arc<Mutex<Lobby>>;
+
+// Not `#[tokio::main]`: it expands to `::core::future`, and our `core` is the cards crate
+fn main() -> std::io::Result<()> {
+    tokio::runtime::Runtime::new()?.block_on(serve())
+}
+
+async fn serve() -> std::io::Result<()> {
+    let address = arg_value("--bind").unwrap_or("127.0.0.1:8080".to_string());
+    let lobby = Arc::new(Mutex::new(Lobby::new(std::env::args().any(|a| a == "--allow-seeded-rooms"))));
+
+    let app = Router::new()
+        .route("/rooms", get(list_rooms))
+        .route("/ws", get(upgrade))
+        .with_state(lobby);
+
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    println!("Serving scopa on ws://{address}/ws (room list on http://{address}/rooms)");
+    axum::serve(listener, app).await
+}
+
+async fn list_rooms(State(lobby): State<Shared>) -> Json<Vec<RoomInfo>> {
+    Json(lobby.lock().expect("Lobby lock poisoned").rooms())
+}
+
+async fn upgrade(ws: WebSocketUpgrade, State(lobby): State<Shared>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| connection(socket, lobby))
+}
+
+/// One client, from connecting to hanging up. Whatever the lobby has to tell it goes
+/// through a channel, so rooms never wait on a slow socket
+async fn connection(socket: WebSocket, lobby: Shared) {
+    let (mut sink, mut stream) = socket.split();
+    let (outbox, mut inbox) = mpsc::unbounded_channel::<ServerEvent>();
+
+    let writer = tokio::spawn(async move {
+        while let Some(event) = inbox.recv().await {
+            let json = serde_json::to_string(&event).expect("Server events always serialize");
+            if sink.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut presence = Presence::Nowhere;
+    while let Some(Ok(msg)) = stream.next().await {
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_)   => break,
+            _                   => continue,
+        };
+        match serde_json::from_str::<ClientRequest>(&text) {
+            Ok(request) => lobby.lock().expect("Lobby lock poisoned").handle(&mut presence, request, &outbox),
+            Err(e)      => { let _ = outbox.send(ServerEvent::Error { message: format!("bad request: {e}") }); },
+        }
+    }
+
+    writer.abort();
+    let _ = writer.await; // So the channel is closed before the lobby checks who's still around
+    lobby.lock().expect("Lobby lock poisoned").disconnect(&presence, &outbox);
+}
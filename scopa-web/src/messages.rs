@@ -0,0 +1,90 @@
+use core::*;
+use scopa::{Game, Move, PointTally, SeatView, Team};
+
+/// What clients send over the websocket, one json object per message
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientRequest {
+    /// Opens a room called `name` and sits you in its first seat. `rules` is a preset
+    /// name (see [`scopa::Rules::preset`]), `seed` picks the deals, on servers that allow it
+    /// (see [`crate::lobby::Lobby::new`])
+    Create { name: String, rules: Option<String>, seed: Option<u64> },
+    /// Takes the next free seat of the room with this join code
+    Join { code: String },
+    /// Watches the room called `room`, without seeing anyone's hand
+    Spectate { room: String },
+    /// Gets your seat back after a dropped connection, with the token from [`ServerEvent::Joined`]
+    Reconnect { token: String },
+    /// A move, written like in the REPL (see the scopa README)
+    Move { input: String },
+}
+
+/// What the server sends back
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// You're in `room`. Players get their seat and team, the join code to share and a token
+    /// to reconnect with, spectators (`you` is `None`) get none of that
+    Joined { room: String, code: Option<String>, you: Option<usize>, team: Option<Team>, token: Option<String> },
+    /// The room still has free seats
+    WaitingForOpponent,
+    /// How the game looks from your seat
+    State(SeatView),
+    /// How the game looks to spectators
+    Public(PublicView),
+    /// `mov` was made (the seat and team that made it are in there)
+    Played { mov: Move },
+    /// Your move wasn't valid (`reason` says why), it's still your turn
+    Rejected { reason: String },
+    /// The points each team got out of the match
+    MatchOver { tally: PointTally, points: Vec<usize> },
+    /// `points` are the totals, by team
+    GameOver { winner: Team, points: Vec<usize> },
+    /// Seat `who` lost their connection, the game waits for them to reconnect
+    PlayerLeft { who: usize },
+    PlayerReturned { who: usize },
+    /// The request couldn't be done
+    Error { message: String },
+}
+
+/// Only what everyone at the table can see: no hands, just how many cards are in them
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PublicView {
+    /// Whose turn it is
+    pub playing: usize,
+    pub table: Deck,
+    pub deck_len: usize,
+    /// By seat
+    pub hand_lens: Vec<usize>,
+    /// By team, as are `scope` and `points`
+    pub pile_lens: Vec<usize>,
+    pub scope: Vec<usize>,
+    pub points: Vec<usize>,
+}
+
+impl PublicView {
+    pub fn of(game: &Game) -> PublicView {
+        let view = game.view();
+        PublicView {
+            playing: game.seat_playing(),
+            table: view.table.clone(),
+            deck_len: view.deck_len,
+            hand_lens: view.hand_lens,
+            pile_lens: view.piles.iter().map(Deck::len).collect(),
+            scope: view.scope,
+            points: game.points.clone(),
+        }
+    }
+}
+
+/// An entry of `GET /rooms`
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RoomInfo {
+    pub name: String,
+    /// Seats taken, out of `seats`
+    pub players: usize,
+    pub seats: usize,
+    pub spectators: usize,
+    pub started: bool,
+    pub over: bool,
+}
@@ -0,0 +1,195 @@
+use tokio::sync::mpsc::UnboundedSender;
+
+use scopa::{Game, Rules, SeatView};
+use crate::messages::{PublicView, RoomInfo, ServerEvent};
+
+/// Where the events for one connection go (its websocket writer is on the other end)
+pub type Outbox = UnboundedSender<ServerEvent>;
+
+/// A player's chair: it stays theirs while they're disconnected, `token` gets it back
+#[derive(Debug)]
+pub struct Seat {
+    pub token: String,
+    pub outbox: Option<Outbox>,
+}
+
+/// One game and everyone in it. The room's [`Game`] is the real one, clients only ever
+/// send move notation and get told what happened
+#[derive(Debug)]
+pub struct Room {
+    pub name: String,
+    pub code: String,
+    pub game: Game,
+    /// One per [`Rules::players`], taken in order
+    pub seats: Vec<Option<Seat>>,
+    pub spectators: Vec<Outbox>,
+    pub over: bool,
+}
+
+impl Room {
+    pub fn new(name: String, code: String, rules: Rules, seed: Option<u64>) -> Room {
+        let rules = rules.competitive(); // Both players would have to agree on an undo
+        let game = match seed {
+            Some(seed) => Game::from_seed(seed, rules),
+            None       => Game::new(rules),
+        };
+        let seats = (0..rules.players).map(|_| None).collect();
+        Room { name, code, game, seats, spectators: vec![], over: false }
+    }
+
+    pub fn info(&self) -> RoomInfo {
+        RoomInfo {
+            name: self.name.clone(),
+            players: self.seats.iter().flatten().count(),
+            seats: self.seats.len(),
+            spectators: self.spectators.len(),
+            started: self.is_full(),
+            over: self.over,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.seats.iter().all(Option::is_some)
+    }
+
+    /// Whether anyone is still connected to the room
+    pub fn is_empty(&self) -> bool {
+        self.seats.iter().flatten().all(|s| s.outbox.is_none()) && self.spectators.is_empty()
+    }
+
+    pub fn has_token(&self, token: &str) -> bool {
+        self.seats.iter().flatten().any(|s| s.token == token)
+    }
+
+    /// Puts a new player in the first free seat, `None` if there isn't one
+    pub fn sit(&mut self, token: String, outbox: Outbox) -> Option<usize> {
+        let seat = self.seats.iter().position(Option::is_none)?;
+
+        let _ = outbox.send(ServerEvent::Joined {
+            room: self.name.clone(),
+            code: Some(self.code.clone()),
+            you: Some(seat),
+            team: Some(self.game.rules.team_of(seat)),
+            token: Some(token.clone()),
+        });
+        self.seats[seat] = Some(Seat { token, outbox: Some(outbox) });
+
+        match self.is_full() {
+            true  => self.send_state(),
+            false => self.send_to(seat, ServerEvent::WaitingForOpponent),
+        }
+        Some(seat)
+    }
+
+    /// Gives the seat with `token` to a new connection. If the old one is still around it
+    /// gets told and cut off, see [`Room::holds`]
+    pub fn reconnect(&mut self, token: &str, outbox: Outbox) -> Option<usize> {
+        let seat = self.seats.iter().position(|s| s.as_ref().is_some_and(|s| s.token == token))?;
+        let away = matches!(&self.seats[seat], Some(Seat { outbox: None, .. }));
+        self.send_to(seat, ServerEvent::Error { message: "your seat was taken back from another connection".to_string() });
+
+        let _ = outbox.send(ServerEvent::Joined {
+            room: self.name.clone(),
+            code: Some(self.code.clone()),
+            you: Some(seat),
+            team: Some(self.game.rules.team_of(seat)),
+            token: Some(token.to_string()),
+        });
+        if let Some(s) = &mut self.seats[seat] {
+            s.outbox = Some(outbox);
+        }
+        if away {
+            self.broadcast(ServerEvent::PlayerReturned { who: seat });
+        }
+
+        match self.is_full() {
+            true  => self.send_state(),
+            false => self.send_to(seat, ServerEvent::WaitingForOpponent),
+        }
+        Some(seat)
+    }
+
+    pub fn spectate(&mut self, outbox: Outbox) {
+        let _ = outbox.send(ServerEvent::Joined { room: self.name.clone(), code: None, you: None, team: None, token: None });
+        if self.is_full() {
+            let _ = outbox.send(ServerEvent::Public(PublicView::of(&self.game)));
+        }
+        self.spectators.push(outbox);
+    }
+
+    /// Whether `outbox` is still the connection playing `seat` (a reconnect moves it elsewhere)
+    pub fn holds(&self, seat: usize, outbox: &Outbox) -> bool {
+        matches!(&self.seats[seat], Some(Seat { outbox: Some(o), .. }) if o.same_channel(outbox))
+    }
+
+    /// `seat`'s connection dropped, the seat waits for them. Nothing happens if the seat
+    /// already went to another connection
+    pub fn leave(&mut self, seat: usize, outbox: &Outbox) {
+        if !self.holds(seat, outbox) {
+            return;
+        }
+        if let Some(s) = &mut self.seats[seat] {
+            s.outbox = None;
+        }
+        self.broadcast(ServerEvent::PlayerLeft { who: seat });
+    }
+
+    /// Tries `input` as `seat`'s move
+    pub fn play(&mut self, seat: usize, input: &str) {
+        if self.over {
+            return self.send_to(seat, ServerEvent::Error { message: "the game is over".to_string() });
+        }
+        if !self.is_full() {
+            return self.send_to(seat, ServerEvent::WaitingForOpponent);
+        }
+        if self.game.seat_playing() != seat {
+            return self.send_to(seat, ServerEvent::Rejected { reason: "it's not your turn".to_string() });
+        }
+
+        let mov = match self.game.make_move(input) {
+            Ok(mov) => mov,
+            Err(e)  => return self.send_to(seat, ServerEvent::Rejected { reason: e.to_string() }),
+        };
+        if let Some(mov) = mov {
+            self.broadcast(ServerEvent::Played { mov });
+        }
+
+        if let Some(tally) = self.game.is_match_over() {
+            let points = self.game.award_points(&tally);
+            self.broadcast(ServerEvent::MatchOver { tally, points });
+
+            if let Some(winner) = self.game.winner() {
+                self.over = true;
+                let points = self.game.points.clone();
+                return self.broadcast(ServerEvent::GameOver { winner, points });
+            }
+            self.game.next_match();
+        } else {
+            self.game.next_turn();
+        }
+        self.send_state();
+    }
+
+    /// Each player gets their own view, spectators the public one
+    fn send_state(&mut self) {
+        for seat in 0..self.seats.len() {
+            self.send_to(seat, ServerEvent::State(SeatView::of(&self.game, seat)));
+        }
+        let public = PublicView::of(&self.game);
+        self.spectators.retain(|s| s.send(ServerEvent::Public(public.clone())).is_ok());
+    }
+
+    fn send_to(&self, seat: usize, event: ServerEvent) {
+        if let Some(Seat { outbox: Some(outbox), .. }) = &self.seats[seat] {
+            let _ = outbox.send(event);
+        }
+    }
+
+    /// To every player and spectator (forgetting the spectators that are gone)
+    fn broadcast(&mut self, event: ServerEvent) {
+        for seat in 0..self.seats.len() {
+            self.send_to(seat, event.clone());
+        }
+        self.spectators.retain(|s| s.send(event.clone()).is_ok());
+    }
+}
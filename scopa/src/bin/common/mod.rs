@@ -0,0 +1,60 @@
+//! Command line flags shared by the binaries, not every one of them uses all of these
+#![allow(dead_code)]
+
+use scopa::{strategy_from_name, Rules, Strategy};
+
+/// Value given to `flag`, as in `--flag value`
+pub fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|a| a == flag)?;
+    args.get(i + 1).cloned()
+}
+
+/// Whether `flag` was passed at all
+pub fn has_flag(flag: &str) -> bool {
+    std::env::args().any(|a| a == flag)
+}
+
+/// `--seed N` picks the deals, otherwise they're random
+pub fn seed_from_args() -> Option<u64> {
+    if !has_flag("--seed") { return None }
+
+    let seed = arg_value("--seed").and_then(|s| s.parse().ok());
+    if seed.is_none() {
+        eprintln!("`--seed` needs a number after it, using a random seed instead");
+    }
+    seed
+}
+
+/// `--rules <preset>` (see [`Rules::preset`]), with no undo if `--competitive` is there too.
+/// Exits on a preset that doesn't exist
+pub fn rules_from_args() -> Rules {
+    let rules = match arg_value("--rules") {
+        None       => Rules::default(),
+        Some(name) => Rules::preset(&name).unwrap_or_else(|| {
+            eprintln!("Unknown rules '{name}': pick one of {}", Rules::PRESETS.join(", "));
+            std::process::exit(1);
+        }),
+    };
+    match has_flag("--competitive") {
+        true  => rules.competitive(),
+        false => rules,
+    }
+}
+
+/// One player per team of `rules`: `--purple <who>`, `--green <who>`, `--orange <who>`...,
+/// see [`strategy_from_name`]. `None` is a human (the default)
+pub fn bots_from_args(rules: &Rules) -> Vec<Option<Box<dyn Strategy>>> {
+    rules.all_teams()
+        .map(|team| bot_from_args(&format!("--{}", team.name().to_lowercase())))
+        .collect()
+}
+
+/// Exits on a player that doesn't exist
+fn bot_from_args(flag: &str) -> Option<Box<dyn Strategy>> {
+    let name = arg_value(flag).unwrap_or("human".to_string());
+    strategy_from_name(&name).unwrap_or_else(|e| {
+        eprintln!("Bad player for `{flag}`: {e}");
+        std::process::exit(1);
+    })
+}
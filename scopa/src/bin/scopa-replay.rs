@@ -0,0 +1,75 @@
+use scopa::*;
+use std::io::stdin;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: scopa-replay <file> (write one with `scopa --replay <file>`)");
+        std::process::exit(1);
+    };
+    let replay = Replay::load(&path).unwrap_or_else(|e| {
+        eprintln!("Could not open '{path}': {e}");
+        std::process::exit(1);
+    });
+    let frames = replay.frames().unwrap_or_else(|e| {
+        eprintln!("The replay doesn't check out: {e}");
+        std::process::exit(1);
+    });
+
+    let mut curr = 0;
+    let mut input = String::new();
+    loop {
+        clear_term();
+        render(&frames[curr], curr, frames.len());
+
+        println!("[Enter/n]ext, [p]revious, [g]oto <frame>, [q]uit");
+        input.clear();
+        if stdin().read_line(&mut input).expect("Could not read from stdin") == 0 {
+            break;
+        }
+
+        match input.trim() {
+            "" | "n" => curr = (curr + 1).min(frames.len() - 1),
+            "p"      => curr = curr.saturating_sub(1),
+            "q"      => break,
+            other    => match other.strip_prefix("g ").and_then(|n| n.trim().parse::<usize>().ok()) {
+                Some(n) => curr = n.min(frames.len() - 1),
+                None    => println!("Unknown command '{other}'"),
+            },
+        }
+    }
+}
+
+fn render(frame: &Frame, i: usize, total: usize) {
+    let game = &frame.game;
+    println!("Frame {i}/{} - match {}, seed {}", total - 1, game.matches_played + 1, game.seed);
+    let score: Vec<String> = game.points.iter().enumerate().map(|(team, p)| format!("{} '{p}'", Team(team))).collect();
+    println!("Score is: {}", score.join(" - "));
+    let first = game.curr_match.first;
+    match &frame.mov {
+        Some(mov) => println!("Last move: {mov}"),
+        None      => println!("Cards were just dealt, {} goes first", seat_name(first, game.rules.team_of(first))),
+    }
+    println!("{}", game.curr_match);
+
+    let hand = |p: &Player| p.curr_hand.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ; ");
+    for (seat, player) in game.curr_match.players.iter().enumerate() {
+        println!("{}'s hand: {}", seat_name(seat, game.rules.team_of(seat)), hand(player));
+    }
+
+    if let Some(tally) = &frame.tally {
+        println!("\nMatch over, the breakdown is:\n{tally}\n");
+        if let Some(nerd) = game.full_napoli() {
+            println!("{nerd} got a full napoli and wins the game");
+        } else if let Some(winner) = game.winner() {
+            let others: Vec<String> = game.points.iter().enumerate()
+                .filter(|&(team, _)| team != winner.0)
+                .map(|(_, p)| p.to_string())
+                .collect();
+            println!("{winner} won with {} points against {}", game.points[winner.0], others.join(" and "));
+        }
+    }
+}
+
+fn clear_term() {
+    print!("{}[2J", 27 as char);
+}
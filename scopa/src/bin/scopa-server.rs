@@ -0,0 +1,115 @@
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+
+use scopa::*;
+mod common;
+use common::*;
+
+/// One of the players: what we read from them and what we write to them
+struct Seat {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Seat {
+    fn new(stream: TcpStream) -> std::io::Result<Seat> {
+        stream.set_nodelay(true)?; // Messages are tiny and each one is waited on
+        Ok(Seat { reader: BufReader::new(stream.try_clone()?), writer: stream })
+    }
+    fn send(&mut self, msg: &ServerMessage) -> std::io::Result<()> {
+        write_message(&mut self.writer, msg)
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let address = arg_value("--bind").unwrap_or("127.0.0.1:7878".to_string());
+    let rules = rules_from_args();
+    let mut game = match seed_from_args() {
+        Some(seed) => Game::from_seed(seed, rules),
+        None       => Game::new(rules),
+    };
+
+    let listener = TcpListener::bind(&address)?;
+    println!("Waiting for players on {address} (connect with `scopa --connect {address}`)");
+
+    // Seats get taken in the order people connect, the first one in plays purple
+    let mut seats = Vec::new();
+    for you in 0..rules.players {
+        let (stream, from) = listener.accept()?;
+        let team = rules.team_of(you);
+        println!("{from} joined as {}", seat_name(you, team));
+        let mut seat = Seat::new(stream)?;
+        seat.send(&ServerMessage::Welcome { you, team, rules: game.rules })?;
+        seats.push(seat);
+    }
+
+    if let Err(gone) = play(&mut game, &mut seats) {
+        println!("{} left, stopping", seat_name(gone, game.rules.team_of(gone)));
+        // Whoever's still there gets told, if they can't be reached either there's nobody left to tell
+        for (_, seat) in seats.iter_mut().enumerate().filter(|&(seat, _)| seat != gone) {
+            let _ = seat.send(&ServerMessage::OpponentLeft);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the game until someone wins, or until a seat can't be read from or written to
+/// anymore (that seat is the error)
+fn play(game: &mut Game, seats: &mut [Seat]) -> Result<(), usize> {
+    let mut everyone_needs_state = true;
+    loop {
+        if everyone_needs_state {
+            for (you, seat) in seats.iter_mut().enumerate() {
+                seat.send(&ServerMessage::State(SeatView::of(game, you))).map_err(|_| you)?;
+            }
+        }
+
+        let playing = game.seat_playing();
+        let input = match read_message(&mut seats[playing].reader) {
+            Ok(Some(ClientMessage::Move { input })) => input,
+            // Garbage doesn't end the game, the player gets to try again
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                let reason = format!("couldn't read that message: {e}");
+                seats[playing].send(&ServerMessage::Rejected { reason }).map_err(|_| playing)?;
+                everyone_needs_state = false;
+                continue;
+            },
+            Ok(None) | Err(_) => return Err(playing),
+        };
+
+        // The server's copy is the only one that counts: the move goes through the same checks as locally
+        let mov = match game.make_move(&input) {
+            Ok(mov) => mov,
+            Err(e)  => {
+                let mover = &mut seats[playing];
+                mover.send(&ServerMessage::Rejected { reason: e.to_string() }).map_err(|_| playing)?;
+                mover.send(&ServerMessage::State(SeatView::of(game, playing))).map_err(|_| playing)?;
+                everyone_needs_state = false;
+                continue;
+            },
+        };
+        everyone_needs_state = true;
+        if let Some(mov) = mov {
+            broadcast(seats, &ServerMessage::Played { mov })?;
+        }
+
+        if let Some(tally) = game.is_match_over() {
+            let points = game.award_points(&tally);
+            broadcast(seats, &ServerMessage::MatchOver { tally, points })?;
+
+            if let Some(winner) = game.winner() {
+                let points = game.points.clone();
+                println!("{winner} won, {}", points.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(" to "));
+                return broadcast(seats, &ServerMessage::GameOver { winner, points, seed: game.seed });
+            }
+            game.next_match();
+        } else {
+            game.next_turn();
+        }
+    }
+}
+
+/// Stops at the first seat that can't be written to, and gives it back
+fn broadcast(seats: &mut [Seat], msg: &ServerMessage) -> Result<(), usize> {
+    seats.iter_mut().enumerate().try_for_each(|(you, s)| s.send(msg).map_err(|_| you))
+}
@@ -0,0 +1,439 @@
+use std::time::Duration;
+
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::execute,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind, EnableMouseCapture, DisableMouseCapture},
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use core::*;
+use scopa::*;
+mod common;
+use common::*;
+
+/// Width of a card on screen, borders included
+const CARD_WIDTH: u16 = 8;
+/// How long to wait between moves when only bots are playing, so they can be followed
+const BOT_DELAY: Duration = Duration::from_millis(700);
+
+fn main() -> std::io::Result<()> {
+    let rules = rules_from_args();
+    let game = match seed_from_args() {
+        Some(seed) => Game::from_seed(seed, rules),
+        None       => Game::new(rules),
+    };
+    let seed = game.seed;
+    let bots = bots_from_args(&rules);
+    let mut app = App::new(game, bots);
+    app.privacy = app.bots.iter().all(Option::is_none) && !has_flag("--no-privacy");
+
+    let mut terminal = ratatui::init();
+    execute!(std::io::stdout(), EnableMouseCapture)?;
+    let result = app.run(&mut terminal);
+    execute!(std::io::stdout(), DisableMouseCapture)?;
+    ratatui::restore();
+
+    println!("Game seed: {seed} (pass `--seed {seed}` to play this exact game again)");
+    result
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Hand,
+    Table,
+}
+
+struct App {
+    game: Game,
+    /// Who plays for each team, `None` for humans
+    bots: Vec<Option<Box<dyn Strategy>>>,
+    focus: Focus,
+    hand_cursor: usize,
+    table_cursor: usize,
+    /// Table cards picked for the next capture
+    picked: Vec<usize>,
+    /// Hand card clicked last, clicking it again plays it
+    clicked: Option<usize>,
+    /// What just happened, or what went wrong
+    status: Line<'static>,
+    /// Results of the match that just ended (and the points each team got), shown until Enter
+    tally: Option<(PointTally, Vec<usize>)>,
+    /// Who won, once someone has
+    game_over: Option<Line<'static>>,
+    /// Hot seat: hide the hand until the next player is the one looking
+    privacy: bool,
+    /// The cards are hidden, waiting for the next player to press Enter
+    hidden: bool,
+    quit: bool,
+    /// Where the cards were drawn last frame, for the mouse
+    hand_areas: Vec<Rect>,
+    table_areas: Vec<Rect>,
+}
+
+impl App {
+    fn new(game: Game, bots: Vec<Option<Box<dyn Strategy>>>) -> App {
+        App {
+            game,
+            bots,
+            focus: Focus::Hand,
+            hand_cursor: 0,
+            table_cursor: 0,
+            picked: vec![],
+            clicked: None,
+            status: Line::from("Pick a card from your hand, and the table cards it takes"),
+            tally: None,
+            game_over: None,
+            privacy: false,
+            hidden: false,
+            quit: false,
+            hand_areas: vec![],
+            table_areas: vec![],
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        while !self.quit {
+            terminal.draw(|f| self.render(f))?;
+
+            let bots_turn = self.tally.is_none() && self.is_bot_turn();
+            let no_humans = self.bots.iter().all(Option::is_some);
+            let wait = match (bots_turn, no_humans) {
+                (false, _)    => None,
+                (true, true)  => Some(BOT_DELAY),
+                (true, false) => Some(Duration::ZERO),
+            };
+            match wait {
+                Some(timeout) if !event::poll(timeout)? => self.bot_move(),
+                _                                       => self.handle(event::read()?),
+            }
+        }
+        Ok(())
+    }
+
+    fn is_bot_turn(&self) -> bool {
+        self.bots[self.game.team_playing().0].is_some()
+    }
+
+    fn bot_move(&mut self) {
+        let team = self.game.team_playing();
+        let bot = self.bots[team.0].as_mut();
+        let action = bot.expect("Only called on a bot's turn").choose(&self.game.view());
+        let mov = self.game.play(&action).expect("Bots only pick moves that can be played");
+        self.status = played_line(team, mov);
+        self.after_move();
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key.code),
+            Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                self.handle_click(Position::new(mouse.column, mouse.row))
+            },
+            _ => {},
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyCode) {
+        if key == KeyCode::Char('q') {
+            self.quit = true;
+            return;
+        }
+        if self.tally.is_some() {
+            if matches!(key, KeyCode::Enter | KeyCode::Char(' ')) { self.next_match() }
+            return;
+        }
+        if self.hidden {
+            if key == KeyCode::Enter { self.hidden = false }
+            return;
+        }
+
+        let hand_len  = self.game.view().hand.len();
+        let table_len = self.game.curr_match.table.len();
+        let (cursor, len) = match self.focus {
+            Focus::Hand  => (&mut self.hand_cursor, hand_len),
+            Focus::Table => (&mut self.table_cursor, table_len),
+        };
+        match key {
+            KeyCode::Left  | KeyCode::Char('h')                 => *cursor = cursor.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l')                 => *cursor = (*cursor + 1).min(len.saturating_sub(1)),
+            KeyCode::Up    | KeyCode::Char('k') if table_len > 0 => self.focus = Focus::Table,
+            KeyCode::Down  | KeyCode::Char('j')                 => self.focus = Focus::Hand,
+            KeyCode::Char(' ') if self.focus == Focus::Table    => self.toggle_pick(self.table_cursor),
+            KeyCode::Enter                                      => self.play_selection(),
+            KeyCode::Char('t') | KeyCode::Char('d')             => self.play_human(&Action::Drop { hand: self.hand_cursor }),
+            KeyCode::Char('u')                                  => self.undo_redo(true),
+            KeyCode::Char('r')                                  => self.undo_redo(false),
+            KeyCode::Esc                                        => self.picked.clear(),
+            _                                                   => {},
+        }
+    }
+
+    fn handle_click(&mut self, at: Position) {
+        if self.tally.is_some() || self.hidden { return }
+
+        if let Some(i) = self.hand_areas.iter().position(|r| r.contains(at)) {
+            self.focus = Focus::Hand;
+            self.hand_cursor = i;
+            if self.clicked.replace(i) == Some(i) {
+                self.play_selection();
+            }
+        } else if let Some(i) = self.table_areas.iter().position(|r| r.contains(at)) {
+            self.focus = Focus::Table;
+            self.table_cursor = i;
+            self.toggle_pick(i);
+        }
+    }
+
+    fn toggle_pick(&mut self, i: usize) {
+        match self.picked.iter().position(|&p| p == i) {
+            Some(at) => { self.picked.remove(at); },
+            None     => self.picked.push(i),
+        }
+    }
+
+    /// Takes the picked table cards with the selected hand card. With nothing picked, the
+    /// hand card takes whatever it can (see the shorthand in the README)
+    fn play_selection(&mut self) {
+        if self.picked.is_empty() {
+            let team = self.game.team_playing();
+            match self.game.make_move(&self.hand_cursor.to_string()) {
+                Ok(mov) => { self.status = played_line(team, mov); self.after_move() },
+                Err(e)  => self.status = Line::from(e.to_string()).red(),
+            }
+        } else {
+            self.play_human(&Action::Capture { hand: self.hand_cursor, table: self.picked.clone() });
+        }
+    }
+
+    fn play_human(&mut self, action: &Action) {
+        let team = self.game.team_playing();
+        match self.game.play(action) {
+            Ok(mov) => { self.status = played_line(team, mov); self.after_move() },
+            Err(e)  => self.status = Line::from(e.to_string()).red(),
+        }
+    }
+
+    /// Ends the match or passes the turn
+    fn after_move(&mut self) {
+        self.picked.clear();
+        self.clicked = None;
+        if let Some(tally) = self.game.is_match_over() {
+            let points = self.game.award_points(&tally);
+            self.game_over = if let Some(nerd) = self.game.full_napoli() {
+                Some(Line::from(vec![team_span(nerd), " got a full napoli and wins the game".into()]))
+            } else {
+                self.game.winner().map(|winner| Line::from(vec![
+                    team_span(winner),
+                    format!(" wins {}", self.game.points.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(" to ")).into(),
+                ]))
+            };
+            self.tally = Some((tally, points));
+        } else {
+            self.game.next_turn();
+            self.hidden = self.privacy;
+        }
+        self.clamp_cursors();
+    }
+
+    fn next_match(&mut self) {
+        if self.game_over.is_some() {
+            self.quit = true;
+            return;
+        }
+        self.tally = None;
+        self.game.next_match();
+        self.focus = Focus::Hand;
+        self.hidden = self.privacy;
+        self.status = Line::from(vec!["New match, ".into(), team_span(self.game.team_playing()), seat_span(&self.game), " goes first".into()]);
+        self.clamp_cursors();
+    }
+
+    /// Against a bot its moves are taken back (or redone) too, until it's a human's turn
+    fn undo_redo(&mut self, undo: bool) {
+        let step = |game: &mut Game| if undo { game.undo() } else { game.redo() };
+        let playing = self.game.seat_playing();
+        let mut result = step(&mut self.game);
+        while result.is_ok() && self.is_bot_turn() {
+            result = step(&mut self.game);
+        }
+        self.hidden = self.privacy && self.game.seat_playing() != playing;
+        self.status = match result {
+            Ok(())  => Line::from(if undo { "Move taken back" } else { "Move played again" }),
+            Err(e)  => Line::from(e.to_string()).red(),
+        };
+        self.picked.clear();
+        self.clamp_cursors();
+    }
+
+    fn clamp_cursors(&mut self) {
+        self.hand_cursor  = self.hand_cursor.min(self.game.view().hand.len().saturating_sub(1));
+        self.table_cursor = self.table_cursor.min(self.game.curr_match.table.len().saturating_sub(1));
+        if self.game.curr_match.table.is_empty() { self.focus = Focus::Hand }
+    }
+
+    fn render(&mut self, f: &mut Frame) {
+        let [header, opponent, table, hand, bottom] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Min(6),
+        ]).areas(f.area());
+        let [history, help] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+
+        let game = &self.game;
+        let view = game.view();
+        let me = view.team;
+
+        let mut score: Vec<Span> = vec![];
+        for (team, points) in game.points.iter().enumerate() {
+            if team > 0 { score.push(" - ".into()) }
+            score.extend([team_span(Team(team)), format!(" {points}").bold()]);
+        }
+        let first = game.curr_match.first;
+        score.extend([
+            format!("   first to {}   match {}   deck: {} cards   ", game.rules.target_score, game.matches_played + 1, view.deck_len).into(),
+            seat_name(first, game.rules.team_of(first)).fg(Color::Indexed(game.rules.team_of(first).color())).bold(), " went first".into(),
+        ]);
+        let score = Line::from(score);
+        f.render_widget(Paragraph::new(score).block(Block::bordered().title(" Scopa ")), header);
+
+        if self.hidden {
+            self.hand_areas.clear();
+            self.table_areas.clear();
+            let message = Text::from(vec![
+                Line::from(vec!["Pass to ".into(), team_span(me), seat_span(game)]),
+                Line::default(),
+                Line::from("Press Enter once nobody else can see the screen"),
+            ]);
+            let popup = centered(f.area(), 52, 5);
+            f.render_widget(Paragraph::new(message).alignment(Alignment::Center).block(Block::bordered()), popup);
+            return;
+        }
+
+        // One line per other team, with both hands when they're two
+        let them: Vec<Line> = game.rules.all_teams().filter(|&t| t != me)
+            .map(|team| {
+                let their_hands: Vec<String> = view.hand_lens.iter().enumerate()
+                    .filter(|&(seat, _)| game.rules.team_of(seat) == team)
+                    .map(|(_, len)| len.to_string())
+                    .collect();
+                Line::from(vec![
+                    team_span(team),
+                    format!(": {} cards in hand   pile: {} cards   scope: {}", their_hands.join(" and "), view.piles[team.0].len(), view.scope[team.0]).into(),
+                ])
+            })
+            .collect();
+        f.render_widget(Paragraph::new(them).block(Block::bordered().title(" Opponents ")), opponent);
+
+        let table_cards: Vec<Card> = view.table.iter().copied().collect();
+        let table_cursor = (self.focus == Focus::Table).then_some(self.table_cursor);
+        let table_block = Block::bordered().title(" Table ");
+        f.render_widget(&table_block, table);
+        self.table_areas = render_cards(f, table_block.inner(table), &table_cards, table_cursor, &self.picked);
+
+        let hand_title = Line::from(vec![
+            " ".into(), team_span(me), "'s hand".into(), seat_span(game),
+            format!("   pile: {} cards   scope: {} ", view.piles[me.0].len(), view.scope[me.0]).into(),
+        ]);
+        let hand_cursor = (self.focus == Focus::Hand).then_some(self.hand_cursor);
+        let hand_block = Block::bordered().title(hand_title);
+        f.render_widget(&hand_block, hand);
+        self.hand_areas = render_cards(f, hand_block.inner(hand), view.hand, hand_cursor, &[]);
+
+        // Newest at the bottom, older ones scroll off the top
+        let events: Vec<Line> = game.curr_match.history.iter().map(|e| Line::from(e.to_string())).collect();
+        let shown = events.len().saturating_sub(history.height.saturating_sub(2) as usize);
+        f.render_widget(Paragraph::new(events[shown..].to_vec()).block(Block::bordered().title(" History ")), history);
+
+        let help_text = Text::from(vec![
+            self.status.clone(),
+            Line::default(),
+            Line::from("←/→ move   ↑/↓ table or hand   space pick a table card"),
+            Line::from("enter play (nothing picked: take the only option)"),
+            Line::from("t place on the table   u/r undo/redo   esc unpick   q quit"),
+            Line::from("Mouse: click table cards to pick them, click a hand card twice to play it").dim(),
+        ]);
+        f.render_widget(Paragraph::new(help_text).wrap(Wrap { trim: true }).block(Block::bordered()), help);
+
+        if let Some((tally, points)) = &self.tally {
+            let gained: Vec<Span> = points.iter().enumerate()
+                .flat_map(|(team, p)| [team_span(Team(team)), format!(" +{p}   ").into()])
+                .collect();
+            let mut lines = vec![
+                Line::from("Match over").bold(),
+                Line::from(gained),
+                Line::default(),
+            ];
+            lines.extend(tally_lines(tally));
+            lines.push(Line::default());
+            lines.push(match &self.game_over {
+                Some(winner) => winner.clone(),
+                None         => Line::from("Enter: next match"),
+            });
+
+            let popup = centered(f.area(), 46, lines.len() as u16 + 2);
+            f.render_widget(Clear, popup);
+            f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).block(Block::bordered()), popup);
+        }
+    }
+}
+
+/// Draws `cards` side by side and returns where each one went (those that didn't fit aren't drawn)
+fn render_cards(f: &mut Frame, area: Rect, cards: &[Card], cursor: Option<usize>, picked: &[usize]) -> Vec<Rect> {
+    let fits = (area.width / CARD_WIDTH) as usize;
+    let areas: Vec<Rect> = (0..cards.len().min(fits))
+        .map(|i| Rect::new(area.x + i as u16 * CARD_WIDTH, area.y, CARD_WIDTH, area.height.min(3)))
+        .collect();
+
+    for (i, (card, &rect)) in cards.iter().zip(&areas).enumerate() {
+        let mut style = Style::new();
+        if picked.contains(&i)  { style = style.fg(Color::Yellow) }
+        if cursor == Some(i)    { style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED) }
+        let widget = Paragraph::new(card.to_string())
+            .alignment(Alignment::Center)
+            .block(Block::bordered().border_style(style))
+            .style(style);
+        f.render_widget(widget, rect);
+    }
+    areas
+}
+
+/// [`PointTally`]'s breakdown, with its tabs turned into columns
+fn tally_lines(tally: &PointTally) -> Vec<Line<'static>> {
+    tally.to_string().lines()
+        .filter(|l| !l.starts_with('='))
+        .map(|l| match l.split_once('\t') {
+            Some((label, value)) => Line::from(format!("{label:<16}{}", value.trim_matches('\t').trim_end_matches(','))),
+            None                 => Line::from(l.to_string()),
+        })
+        .collect()
+}
+
+fn played_line(team: Team, mov: Option<Move>) -> Line<'static> {
+    let what = mov.map(|m| m.to_string()).unwrap_or("nothing".to_string());
+    Line::from(vec![team_span(team), format!(" played: {what}").into()])
+}
+
+/// The team's name, in its color ([`Team`]'s `Display` uses escape codes, which don't mix with the TUI)
+fn team_span(team: Team) -> Span<'static> {
+    team.name().fg(Color::Indexed(team.color())).bold()
+}
+
+/// Which of the two players of a team is playing, when teams are pairs (nothing otherwise)
+fn seat_span(game: &Game) -> Span<'static> {
+    match game.rules.players > game.rules.teams {
+        true  => format!(" ({})", seat_name(game.curr_match.seat, game.team_playing())).into(),
+        false => "".into(),
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width  = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
@@ -0,0 +1,303 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use core::*;
+use crate::{Action, GreedyBot, Match, Player, PlayerView, Rules, Strategy, Team};
+
+/// Information set Monte Carlo tree search: every iteration deals the cards we can't see
+/// (everyone else's hands and the deck) at random, plays the match out and scores it with the
+/// real end of match tally. The tree is shared by all those deals, so the bot ends up
+/// picking the move that does best on average over everything the others could hold.
+/// With partners the rewards are the team's, so it plays for them too
+#[derive(Clone, Debug)]
+pub struct IsmctsBot {
+    iterations: usize,
+    rng: StdRng,
+}
+
+/// Preset iteration budgets, from "barely looks ahead" to "takes a second to think"
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn iterations(self) -> usize {
+        match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Easy     => 100,
+            Difficulty::Medium   => 500,
+            Difficulty::Hard     => 2_000,
+            Difficulty::Expert   => 10_000,
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "beginner" => Ok(Difficulty::Beginner),
+            "easy"     => Ok(Difficulty::Easy),
+            "medium"   => Ok(Difficulty::Medium),
+            "hard"     => Ok(Difficulty::Hard),
+            "expert"   => Ok(Difficulty::Expert),
+            _          => Err(format!("'{s}' is not a difficulty (beginner, easy, medium, hard or expert)")),
+        }
+    }
+}
+
+impl IsmctsBot {
+    /// More iterations, stronger (and slower) play
+    pub fn new(iterations: usize) -> IsmctsBot {
+        IsmctsBot { iterations: iterations.max(1), rng: StdRng::from_entropy() }
+    }
+    pub fn with_difficulty(difficulty: Difficulty) -> IsmctsBot {
+        IsmctsBot::new(difficulty.iterations())
+    }
+    /// Same seed and same position, same move
+    pub fn from_seed(iterations: usize, seed: u64) -> IsmctsBot {
+        IsmctsBot { iterations: iterations.max(1), rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+/// How much exploring unvisited moves is worth compared to exploiting good ones
+const EXPLORATION: f64 = 0.7;
+/// Odds of a playout picking the greedy move rather than a random one
+const GREEDY_PLAYOUTS: f64 = 0.7;
+
+impl Strategy for IsmctsBot {
+    fn choose(&mut self, view: &PlayerView) -> Action {
+        let actions = view.legal_moves();
+        if actions.len() == 1 {
+            return actions[0].clone();
+        }
+
+        let mut tree = vec![Node::root(view.team)];
+        for _ in 0..self.iterations {
+            let mut sim = Simulation::determinize(view, &mut self.rng);
+            let mut node = 0;
+
+            // Selection and expansion
+            while !sim.over {
+                let legal: Vec<(Action, CardMove)> = sim.m.legal_moves().into_iter()
+                    .map(|a| { let m = CardMove::new(&sim.m.view(), &a); (a, m) })
+                    .collect();
+
+                let mut untried = Vec::new();
+                for (action, mov) in &legal {
+                    match tree[node].children.iter().find(|&&c| tree[c].mov.as_ref() == Some(mov)) {
+                        Some(&child) => tree[child].availability += 1.0,
+                        None         => untried.push((action, mov)),
+                    }
+                }
+
+                if let Some(&(action, mov)) = untried.choose(&mut self.rng) {
+                    let child = tree.len();
+                    tree.push(Node::new(mov.clone(), sim.m.team(), node));
+                    tree[node].children.push(child);
+                    sim.apply(action);
+                    node = child;
+                    break;
+                }
+
+                let (action, child) = legal.iter()
+                    .filter_map(|(a, m)| tree[node].children.iter()
+                        .find(|&&c| tree[c].mov.as_ref() == Some(m))
+                        .map(|&c| (a, c)))
+                    .max_by(|(_, a), (_, b)| tree[*a].ucb().total_cmp(&tree[*b].ucb()))
+                    .expect("Every legal move has already been tried");
+                sim.apply(action);
+                node = child;
+            }
+
+            // Playout
+            while !sim.over {
+                let view = sim.m.view();
+                let action = if self.rng.gen_bool(GREEDY_PLAYOUTS) {
+                    GreedyBot.choose(&view)
+                } else {
+                    view.legal_moves().choose(&mut self.rng).unwrap().clone()
+                };
+                sim.apply(&action);
+            }
+
+            // Backpropagation
+            let points = sim.points();
+            let mut curr = Some(node);
+            while let Some(n) = curr {
+                // Against whoever did best of the rest, as with three teams that's who to beat
+                let mover = tree[n].mover;
+                let best_other = points.iter().enumerate()
+                    .filter(|&(t, _)| t != mover.0)
+                    .map(|(_, &p)| p)
+                    .max()
+                    .unwrap_or(0);
+                let diff = points[mover.0] as f64 - best_other as f64;
+                tree[n].visits += 1.0;
+                tree[n].reward += 0.5 + 0.5 * (diff / 4.0).tanh();
+                curr = tree[n].parent;
+            }
+        }
+
+        let best = tree[0].children.iter()
+            .max_by(|&&a, &&b| tree[a].visits.total_cmp(&tree[b].visits))
+            .and_then(|&c| tree[c].mov.as_ref())
+            .expect("The search tried at least one move");
+        actions.into_iter()
+            .find(|a| &CardMove::new(view, a) == best)
+            .expect("The chosen move comes from the actual position")
+    }
+}
+
+/// A move written down by its cards rather than indices, so it means the same thing
+/// in every determinization (where the opponent's cards sit in different places)
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CardMove {
+    played: Card,
+    taken: Option<Vec<Card>>,
+}
+
+impl CardMove {
+    fn new(view: &PlayerView, action: &Action) -> CardMove {
+        CardMove {
+            played: view.hand[action.hand_index()],
+            taken: match action {
+                Action::Drop { .. }           => None,
+                Action::Capture { table, .. } => Some(table.iter().map(|&i| view.table[i]).collect()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// Move that led here (None for the root)
+    mov: Option<CardMove>,
+    /// Who made `mov`, rewards are from their point of view
+    mover: Team,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: f64,
+    /// Times this move was legal when its parent got visited
+    availability: f64,
+    reward: f64,
+}
+
+impl Node {
+    fn root(mover: Team) -> Node {
+        Node { mov: None, mover, parent: None, children: vec![], visits: 0.0, availability: 1.0, reward: 0.0 }
+    }
+    fn new(mov: CardMove, mover: Team, parent: usize) -> Node {
+        Node { mov: Some(mov), mover, parent: Some(parent), ..Node::root(mover) }
+    }
+    fn ucb(&self) -> f64 {
+        self.reward / self.visits + EXPLORATION * (self.availability.ln() / self.visits).sqrt()
+    }
+}
+
+/// One possible version of the match: what we see, plus a guess at what we don't
+struct Simulation {
+    m: Match,
+    /// Seat that took last
+    last_taker: usize,
+    over: bool,
+}
+
+impl Simulation {
+    fn determinize<R: Rng + ?Sized>(view: &PlayerView, rng: &mut R) -> Simulation {
+        let seen: Vec<&Card> = view.hand.iter()
+            .chain(view.table.iter())
+            .chain(view.piles.iter().flat_map(|p| p.iter()))
+            .chain(view.shown.iter().flatten())
+            .collect();
+        let mut unseen: Deck = Deck::standard_40().iter().filter(|c| !seen.contains(c)).copied().collect();
+        unseen.shuffle(rng);
+
+        // Only each team's totals matter for the tally, so their piles can sit with anyone on it.
+        // Cards shown for a declaration stay with whoever showed them, the rest is a guess
+        let mut players: Vec<Player> = view.hand_lens.iter().zip(&view.shown).enumerate()
+            .map(|(seat, (&len, shown))| Player {
+                curr_hand: match seat == view.seat {
+                    true  => view.hand.to_vec(),
+                    false => shown.iter().copied().chain(unseen.draw(len - shown.len())).collect(),
+                },
+                ..Player::default()
+            })
+            .collect();
+        for (team, (pile, &scope)) in view.piles.iter().zip(&view.scope).enumerate() {
+            players[team].pile = pile.clone(); // Seat `team` is always on `team`
+            players[team].scope = scope;
+        }
+
+        Simulation {
+            m: Match {
+                seat: view.seat,
+                first: view.seat, // Only matters for undo, which is off
+                players,
+                deck: unseen,
+                table: view.table.clone(),
+                rules: Rules { allow_undo: false, ..view.rules }, // No point in paying for snapshots here
+                history: vec![],
+                undo_stack: vec![],
+                redo_stack: vec![],
+            },
+            last_taker: view.last_taker.unwrap_or(view.seat),
+            over: false,
+        }
+    }
+
+    /// Same as a move in a real game: play, remember who took, give away the table at the end
+    fn apply(&mut self, action: &Action) {
+        let mov = self.m.play(action).expect("Simulations only play legal moves");
+        if let Some(m @ crate::Move { cards_taken: Some(_), .. }) = mov {
+            self.last_taker = m.seat;
+        }
+
+        if self.m.is_over() {
+            let taker = &mut self.m.players[self.last_taker];
+            self.m.table.move_all_cards_to(&mut taker.pile);
+            self.over = true;
+        } else {
+            self.m.next_turn();
+        }
+    }
+
+    /// Each team's points for the finished match
+    fn points(&self) -> Vec<usize> {
+        self.m.tally_final_points().points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn determinizations_keep_shown_cards() {
+        // Seat 0 has nothing to show, seat 1 gets 1+2+4 and shows it for a busso
+        let (mine, busso) = ([Card::new(Suit::Coppe, 1), Card::new(Suit::Coppe, 6), Card::new(Suit::Spade, 5)],
+                              [Card::denari(1), Card::new(Suit::Coppe, 2), Card::new(Suit::Spade, 4)]);
+        let dealt: Vec<Card> = mine.iter().zip(&busso).flat_map(|(&a, &b)| [a, b]).collect();
+        let mut deck: Deck = Deck::standard_40().iter().copied().filter(|c| !dealt.contains(c)).collect();
+        for &card in dealt.iter().rev() {
+            deck.push_to_top(card);
+        }
+        let m = Match::deal(deck, Rules::cirulla(), 0);
+        let view = m.view_of(0);
+        assert_eq!(view.shown[1], busso);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let sim = Simulation::determinize(&view, &mut rng);
+            assert_eq!(sim.m.players[1].curr_hand, busso);
+            assert!(busso.iter().all(|c| !sim.m.deck.contains(c)));
+        }
+    }
+}
@@ -1,134 +1,314 @@
 use std::fmt::{Display, Formatter};
 use std::fmt::Debug;
-use std::cmp::Ordering;
 
 mod parse_move;
+mod strategy;
+mod ismcts;
+mod rules;
+mod replay;
+#[cfg(feature = "serde")]
+mod protocol;
 use crate::parse_move::*;
+pub use crate::rules::*;
+pub use crate::replay::*;
+#[cfg(feature = "serde")]
+pub use crate::protocol::*;
+pub use crate::strategy::*;
+pub use crate::ismcts::*;
 use core::*;
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
-    pub curr_hand: Vec<Card>, // Three or less held cards
+    pub curr_hand: Vec<Card>, // Held cards (three or less, except in scopone)
     pub pile: Deck,           // Cards that they've won
     pub scope: usize,         // nº of scope obtained
 }
 
-#[derive(Clone, Debug, Default, Copy, PartialEq, Eq)]
-pub enum PlayerKind {
-    #[default]
-    Purple,
-    Green
+/// A team, by number. Seats take turns between teams (see [`Rules::team_of`]), so partners
+/// never play one right after the other. Whoever plays alone is a team of their own
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Team(pub usize);
+
+impl Team {
+    pub const PURPLE: Team = Team(0);
+    pub const GREEN:  Team = Team(1);
+    pub const ORANGE: Team = Team(2);
+
+    /// Its name, without the colors `Display` adds
+    pub fn name(self) -> String {
+        match self.0 {
+            0 => "Purple".to_string(),
+            1 => "Green".to_string(),
+            2 => "Orange".to_string(),
+            n => format!("Team {}", n + 1),
+        }
+    }
+
+    /// Its color, as an index of the terminal's 256 color palette
+    pub fn color(self) -> u8 {
+        match self.0 {
+            0 => 93,
+            1 => 34,
+            2 => 208,
+            _ => 244,
+        }
+    }
 }
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
-    pub purple_points: usize, // Host, probably
-    pub green_points: usize,
+    /// Running score, by team
+    pub points: Vec<usize>,
     pub curr_match: Match,
-    pub who_is_first: PlayerKind,
-    pub who_won_last_round: Turn,
+    /// Seat that took cards last this match, whatever's left on the table goes to their team
+    pub last_taker: usize,
     pub last_move: Option<Move>,
+    /// Every match of the game is dealt from this (see [`Game::next_match`])
+    pub seed: u64,
+    /// Matches dealt before the current one
+    pub matches_played: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: Rules,
+    /// Moves of every match before the current one, for [`Game::replay`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub past_moves: Vec<Vec<Move>>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
-    pub turn: Turn,
+    /// Who made it, see [`Match::seat`]
+    pub seat: usize,
+    /// `seat`'s team
+    pub team: Team,
     pub card_played: Card,
     pub cards_taken: Option<Vec<Card>>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Match {
-    pub turn: Turn,
-    pub player_first: Player,
-    pub player_shuffler: Player,
+    /// Whose turn it is. Seats play in order, going back to 0 after the last one
+    pub seat: usize,
+    /// Who played first this match, the shuffler is the seat right before
+    pub first: usize,
+    /// Everyone at the table, by seat
+    pub players: Vec<Player>,
     pub deck: Deck,
     pub table: Deck,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: Rules,
+    /// Everything that happened this match, oldest first
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub history: Vec<Event>,
+    /// How the match looked before each move, for [`Match::undo`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub undo_stack: Vec<Match>,
+    /// Moves taken back, for [`Match::redo`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub redo_stack: Vec<Match>,
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub enum Turn {
-    #[default]
-    First,
-    Shuffler
+/// An entry in [`Match::history`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// Someone made a move
+    Played(Move),
+    /// `seat` (of `team`) ran out of cards and got dealt `cards` from the deck
+    Dealt { seat: usize, team: Team, cards: Vec<Card> },
+    /// The match ended and whatever was left on the table went to `team`, who took last
+    TableSwept { team: Team, cards: Vec<Card> },
+    /// `seat` scored `bonus` with `cards`: the starting table they took, or the hand they showed
+    Bonus { seat: usize, team: Team, bonus: Bonus, cards: Vec<Card> },
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct PointTally {
-    scope_first: usize,
-    scope_shuf: usize,
-    num_cards: Option<Turn>,
-    num_denari: Option<Turn>,
-    sette_bello: Turn,
-    re_bello: Turn,
-    napoli: Option<(Turn, usize)>,
-    primiera: Option<Turn>,
+/// Scope scored outside of plays, in Cirulla (see [`Rules::table_bonus`] and [`Rules::declarations`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Bonus {
+    /// The starting table added up to 15
+    Fifteen,
+    /// The starting table added up to 30
+    Thirty,
+    /// A hand adding up to less than 10 (bussare)
+    Busso,
+    /// Three cards of the same value
+    Decino,
 }
 
-impl PointTally {
-    pub fn first_points(&self) -> usize {
-        count_points(self, Turn::First)
-    }
-    pub fn shuf_points(&self) -> usize {
-        count_points(self, Turn::Shuffler)
+impl Bonus {
+    /// How many scope it's worth
+    pub fn scope(self) -> usize {
+        match self {
+            Bonus::Fifteen => 1,
+            Bonus::Thirty  => 2,
+            Bonus::Busso   => 3,
+            Bonus::Decino  => 10,
+        }
     }
 }
 
-fn count_points(tally: &PointTally, turn: Turn) -> usize {
-    let mut p = 0;
-
-    if turn == Turn::First { p += tally.scope_first }
-    else { p += tally.scope_shuf }
-
-    p += [tally.num_cards   == Some(turn),
-          tally.num_denari  == Some(turn),
-          tally.sette_bello == turn,
-          tally.re_bello    == turn, 
-          tally.primiera    == Some(turn),
-    ].into_iter().filter(|&b| b).count();
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointTally {
+    /// By team, like every other `Vec` in here
+    scope: Vec<usize>,
+    num_cards: Option<Team>,
+    num_denari: Option<Team>,
+    sette_bello: Option<Team>,
+    re_bello: Option<Team>,
+    napoli: Option<(Team, usize)>,
+    grande: Option<Team>,
+    piccola: Option<(Team, usize)>,
+    primiera: Option<Team>,
+    /// Primiera scores (see [`primiera`]), `None` for whoever is missing a suit
+    primiera_scores: Vec<Option<usize>>,
+}
 
-    if let Some((napoli_turn, amount)) = tally.napoli {
-        if napoli_turn == turn { p += amount }
+impl PointTally {
+    /// `team`'s primiera score, `None` if they didn't take a card of every suit
+    pub fn primiera_of(&self, team: Team) -> Option<usize> {
+        self.primiera_scores.get(team.0).copied().flatten()
     }
+    /// Points `team` made this match
+    pub fn points_of(&self, team: Team) -> usize {
+        let mut p = self.scope.get(team.0).copied().unwrap_or(0);
+
+        p += [self.num_cards   == Some(team),
+              self.num_denari  == Some(team),
+              self.sette_bello == Some(team),
+              self.re_bello    == Some(team),
+              self.primiera    == Some(team),
+        ].into_iter().filter(|&b| b).count();
+
+        if let Some((napoli_team, amount)) = self.napoli {
+            if napoli_team == team { p += amount }
+        }
+        if self.grande == Some(team) { p += 5 }
+        if let Some((piccola_team, amount)) = self.piccola {
+            if piccola_team == team { p += amount }
+        }
 
-    p
+        p
+    }
+    /// Everyone's points this match, by team
+    pub fn points(&self) -> Vec<usize> {
+        (0..self.scope.len()).map(|t| self.points_of(Team(t))).collect()
+    }
 }
 
 impl Game {
-    pub fn new() -> Game {
+    pub fn new(rules: Rules) -> Game {
+        Game::from_seed(rand::random(), rules)
+    }
+
+    /// A game whose every deal is decided by `seed`: same seed, same game
+    pub fn from_seed(seed: u64, rules: Rules) -> Game {
         Game {
-            who_is_first: PlayerKind::Purple,
-            curr_match: Match::new(),
-            purple_points: 0,
-            green_points:  0,
-            who_won_last_round: Turn::First,
-            last_move: None
+            points: vec![0; rules.teams],
+            curr_match: Match::deal(Deck::shuffled_from_seed(seed), rules, 0),
+            last_taker: 0,
+            last_move: None,
+            seed,
+            matches_played: 0,
+            rules,
+            past_moves: vec![],
         }
     }
 
-    pub fn make_move<'a>(&'a mut self, mov: &'a str) -> Result<Option<Move>, MoveError> {
+    /// Deals a fresh match (from this game's seed), the next seat along goes first
+    pub fn next_match(&mut self) {
+        self.past_moves.push(self.curr_match.moves().cloned().collect());
+        self.matches_played += 1;
+        let seed = self.seed.wrapping_add(self.matches_played as u64);
+        let first = self.matches_played % self.rules.players;
+        self.curr_match = Match::deal(Deck::shuffled_from_seed(seed), self.rules, first);
+        self.last_taker = first;
+    }
+
+    pub fn make_move(&mut self, mov: &str) -> Result<Option<Move>, MoveError> {
         let m = self.curr_match.make_move(mov)?;
-        if let Some(move_made) = &m {
-            self.who_won_last_round = move_made.turn;
-        }
+        self.record_last_taker(&m);
         Ok(m)
     }
-    pub fn toggle_turn(&mut self) {
-        self.curr_match.turn.toggle_turn()
-    }
-    pub fn winner(&self) -> Option<(String, usize, usize)> {
-        let (purp, grep) = (self.purple_points, self.green_points);
-        let (purple_win, green_win) = (Some((purple_text(), purp, grep)), Some((green_text(),  grep, purp)));
-
-        match (purp, grep) {
-            (0..=20, 0..=20)  => None,
-            (21..,   0..=20)  => purple_win,
-            (0..=20, 21..  )  => green_win,
-            (p, g)            => match p.cmp(&g) {
-                Ordering::Less    => green_win,
-                Ordering::Equal   => None,
-                Ordering::Greater => purple_win,
-            }
+    pub fn play(&mut self, action: &Action) -> Result<Option<Move>, MoveError> {
+        let m = self.curr_match.play(action)?;
+        self.record_last_taker(&m);
+        Ok(m)
+    }
+    /// [`Match::undo`], also putting back who took last
+    pub fn undo(&mut self) -> Result<(), UndoError> {
+        self.curr_match.undo()?;
+        self.sync_with_history();
+        Ok(())
+    }
+    /// [`Match::redo`], also putting back who took last
+    pub fn redo(&mut self) -> Result<(), UndoError> {
+        self.curr_match.redo()?;
+        self.sync_with_history();
+        Ok(())
+    }
+    /// Works out who took last and what the last move was from the match's history
+    fn sync_with_history(&mut self) {
+        let moves = self.curr_match.moves().rev();
+        self.last_taker = moves.clone()
+            .find(|m| m.cards_taken.is_some())
+            .map(|m| m.seat)
+            .unwrap_or(self.curr_match.first);
+        self.last_move = moves.clone().next().cloned();
+    }
+    fn record_last_taker(&mut self, m: &Option<Move>) {
+        if let Some(Move { seat, cards_taken: Some(_), .. }) = m {
+            self.last_taker = *seat;
         }
+        self.last_move.clone_from(m);
+    }
+    /// Passes the turn to the next seat, see [`Match::next_turn`]
+    pub fn next_turn(&mut self) {
+        self.curr_match.next_turn()
+    }
+    /// [`Match::view`], plus who took last
+    pub fn view(&self) -> PlayerView<'_> {
+        PlayerView { last_taker: Some(self.last_taker), ..self.curr_match.view() }
+    }
+    /// [`Match::view_of`], plus who took last
+    pub fn view_of(&self, seat: usize) -> PlayerView<'_> {
+        PlayerView { last_taker: Some(self.last_taker), ..self.curr_match.view_of(seat) }
+    }
+    /// Adds the points of a finished match to the running score, returns what each team got
+    pub fn award_points(&mut self, tally: &PointTally) -> Vec<usize> {
+        let points = tally.points();
+        for (total, p) in self.points.iter_mut().zip(&points) {
+            *total += p;
+        }
+        points
+    }
+    /// Everything needed to play this game again, see [`Replay`]
+    pub fn replay(&self) -> Replay {
+        let mut matches = self.past_moves.clone();
+        matches.push(self.curr_match.moves().cloned().collect());
+        Replay { seed: self.seed, rules: self.rules, matches }
+    }
+
+    /// Who won the game, if anyone has yet: a full napoli wins on the spot, otherwise it's
+    /// whoever has the most points once someone reaches the target (nobody, if that's a tie)
+    pub fn winner(&self) -> Option<Team> {
+        self.full_napoli().or_else(|| {
+            let best = *self.points.iter().max()?;
+            if best < self.rules.target_score { return None }
+            most(&self.points)
+        })
+    }
+
+    /// Who took every denari card this match, if that wins the game under the current rules
+    pub fn full_napoli(&self) -> Option<Team> {
+        if !self.rules.full_napoli_wins { return None }
+
+        self.rules.all_teams().find(|&t| has_full_napoli(&self.curr_match.pile_of(t)))
     }
 
     pub fn is_match_over(&mut self) -> Option<PointTally> {
@@ -141,33 +321,41 @@ impl Game {
     }
 
     pub fn give_table_to_last_taker(&mut self) {
-        let player: &mut Player = match self.who_won_last_round {
-            Turn::First    => &mut self.curr_match.player_first,
-            Turn::Shuffler => &mut self.curr_match.player_shuffler,
-        };
+        let player: &mut Player = &mut self.curr_match.players[self.last_taker];
+
+        if self.curr_match.table.is_empty() { return }
 
+        let cards: Vec<Card> = self.curr_match.table.iter().copied().collect();
         for _ in 0..self.curr_match.table.len() {
             player.pile.push_to_top(self.curr_match.table.take_from_top().unwrap());
         }
+        self.curr_match.history.push(Event::TableSwept { team: self.rules.team_of(self.last_taker), cards });
     }
 
-    pub fn color_playing(&self) -> PlayerKind {
-        use PlayerKind as PK;
-        match (self.curr_match.turn, self.who_is_first) {
-            (Turn::First,    PK::Purple) => PK::Purple,
-            (Turn::First,    PK::Green)  => PK::Green,
-            (Turn::Shuffler, PK::Purple) => PK::Green,
-            (Turn::Shuffler, PK::Green)  => PK::Purple,
-        }
+    /// Whose turn it is
+    pub fn seat_playing(&self) -> usize {
+        self.curr_match.seat
+    }
+    /// The team of whoever's playing
+    pub fn team_playing(&self) -> Team {
+        self.curr_match.team()
     }
-    pub fn toggle_whose_first(&mut self) {
-        self.who_is_first = !self.who_is_first;
+    /// Writes the whole game (score, current match, whose turn...) to `path` as json
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), SaveError> {
+        let json = serde_json::to_string_pretty(self).map_err(SaveError::Format)?;
+        std::fs::write(path, json).map_err(SaveError::Io)
+    }
+
+    /// Reads back a game written by [`Game::save`]
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Game, SaveError> {
+        let json = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+        serde_json::from_str(&json).map_err(SaveError::Format)
     }
+
     pub fn print_cards_of_curr_player(&self) {
-        let cards = match self.curr_match.turn {
-            Turn::First    => &self.curr_match.player_first.curr_hand,
-            Turn::Shuffler => &self.curr_match.player_shuffler.curr_hand,
-        };
+        let cards = &self.curr_match.players[self.curr_match.seat].curr_hand;
 
         let s: String = cards.iter().enumerate().map(|(i, c)| format!("{c}({i})")).collect::<Vec<String>>().join(" ; ");
         println!("{s}");
@@ -178,63 +366,145 @@ impl Game {
 
 impl Match {
     pub fn new() -> Match {
-        let mut deck = Card::shuffled_deck();
+        Match::from_deck(Card::shuffled_deck())
+    }
 
-        let mut player_first = Player::default();
-        let mut player_shuffler = Player::default();
+    /// Deals from [`Deck::shuffled_from_seed`], so the same seed always gives the same match
+    pub fn from_seed(seed: u64) -> Match {
+        Match::from_deck(Deck::shuffled_from_seed(seed))
+    }
 
-        for _ in 0..3 {
-            let (c1, c2) = (deck.take_from_top().unwrap(), deck.take_from_top().unwrap());
+    /// Deals from the top of an already prepared deck
+    pub fn from_deck(deck: Deck) -> Match {
+        Match::deal(deck, Rules::default(), 0)
+    }
 
-            player_first.curr_hand.push(c1);
-            player_shuffler.curr_hand.push(c2);
+    /// Deals a match of `rules` from the top of `deck`: [`Rules::hand_size`] cards to each of
+    /// the [`Rules::players`], one at a time starting from `first` (who plays first), then
+    /// [`Rules::table_cards`] face up. Cirulla's bonuses for the table and the hands get
+    /// scored right away
+    pub fn deal(mut deck: Deck, rules: Rules, first: usize) -> Match {
+        let mut hands = deck.deal(rules.hand_size, rules.players);
+        hands.rotate_right(first);
+        let players = hands.into_iter()
+            .map(|curr_hand| Player { curr_hand, ..Player::default() })
+            .collect();
+
+        let table = deck.draw(rules.table_cards).into_iter().collect();
+
+        let mut m = Match {
+            players, deck, table, rules, first,
+            seat: first,
+            history: vec![],
+            undo_stack: vec![],
+            redo_stack: vec![],
+        };
+        if let Some(bonus) = table_bonus(&m.table, &rules) {
+            let shuffler = (first + rules.players - 1) % rules.players;
+            let cards: Vec<Card> = m.table.iter().copied().collect();
+            m.table.move_all_cards_to(&mut m.players[shuffler].pile);
+            m.players[shuffler].scope += bonus.scope();
+            m.history.push(Event::Bonus { seat: shuffler, team: rules.team_of(shuffler), bonus, cards });
         }
-
-        let mut table = Deck::default();
-        for _ in 0..4 {
-            let c = deck.take_from_top().unwrap();
-            table.push_to_top(c);
+        for seat in (0..rules.players).map(|i| (first + i) % rules.players) {
+            m.declare(seat);
         }
+        m
+    }
 
-        Match { player_first, player_shuffler, deck, table, turn: Turn::First }
+    /// Shows `seat`'s hand for its bonus if it has one, see [`Rules::declarations`]
+    fn declare(&mut self, seat: usize) {
+        let player = &mut self.players[seat];
+        if let Some(bonus) = declaration(&player.curr_hand, &self.rules) {
+            player.scope += bonus.scope();
+            self.history.push(Event::Bonus { seat, team: self.rules.team_of(seat), bonus, cards: player.curr_hand.clone() });
+        }
     }
 
     pub fn is_over(&self) -> bool {
-        self.deck.is_empty()
-            && self.player_first.curr_hand.is_empty()
-            && self.player_shuffler.curr_hand.is_empty()
+        self.deck.is_empty() && self.players.iter().all(|p| p.curr_hand.is_empty())
+    }
+
+    /// The team of whoever's playing
+    pub fn team(&self) -> Team {
+        self.rules.team_of(self.seat)
+    }
+
+    /// Passes the turn to the next seat (moves don't do it on their own)
+    pub fn next_turn(&mut self) {
+        self.seat = (self.seat + 1) % self.players.len();
+    }
+
+    /// Everything `team` has taken this match, partners' piles together
+    pub fn pile_of(&self, team: Team) -> Deck {
+        self.members(team).flat_map(|p| p.pile.iter().copied()).collect()
+    }
+
+    /// Scope made by `team` this match
+    pub fn scope_of(&self, team: Team) -> usize {
+        self.members(team).map(|p| p.scope).sum()
+    }
+
+    fn members(&self, team: Team) -> impl Iterator<Item = &Player> {
+        self.players.iter().enumerate()
+            .filter(move |&(seat, _)| self.rules.team_of(seat) == team)
+            .map(|(_, p)| p)
+    }
+
+    /// Parses `input` (see the README for the notation) and plays it, see [`Match::play`]
+    pub fn make_move(&mut self, input: &str) -> Result<Option<Move>, MoveError> {
+        let action = self.parse_move(input)?;
+        self.play(&action)
     }
 
     /// Returns a Result, that means
-    /// - Ok(Option<Turn>): Is Some if the last move was a take, None if it was placing on the table (for keeping track of the last person to take)
+    /// - Ok(Some(Move)): The move that was made, be it a take or placing on the table
     /// - Err(...): Read the docs for MoveError
-    pub fn make_move<'a>(&'a mut self, input: &'a str) -> Result<Option<Move>, MoveError> {
-        let mov = Self::parse_move(input)?;
+    pub fn play(&mut self, action: &Action) -> Result<Option<Move>, MoveError> {
         let last_move;
-
-        let player = match self.turn {
-            Turn::First => &mut self.player_first,
-            Turn::Shuffler => &mut self.player_shuffler,
+        let (seat, team) = (self.seat, self.team());
+        let others_empty = self.players.iter().enumerate()
+            .all(|(s, p)| s == seat || p.curr_hand.is_empty());
+
+        let before = self.rules.allow_undo.then(|| self.snapshot());
+
+        let player = &mut self.players[seat];
+
+        let hand_len = player.curr_hand.len();
+        let hand_card: Card = *player.curr_hand.get(action.hand_index())
+            .ok_or(MoveError::OutOfRangeOfHand { index: action.hand_index(), hand_len })?;
+
+        // With asso piglia tutto, `a;` and `ta` are the same move: the ace takes everything,
+        // unless the table is empty and it stays there
+        let hand = action.hand_index();
+        let ace_stays = self.table.is_empty() && self.rules.ace_on_empty_table == AceOnEmptyTable::Stays;
+        let action = &match (self.rules.ace_sweeps(hand_card), ace_stays) {
+            (true, true)  => Action::Drop { hand },
+            (true, false) => Action::Capture { hand, table: vec![] },
+            (false, _)    => action.clone(),
         };
 
-        let hand_card: Card = *player.curr_hand.get(mov.from)
-            .ok_or(MoveError::OutOfRangeOfHand)?;
+        if let Action::Capture { table: to_indices, .. } = action {
+            let mut to_indices = to_indices.clone();
+            to_indices.sort_unstable();
+            if let Some(w) = to_indices.windows(2).find(|w| w[0] == w[1]) {
+                return Err(MoveError::RepeatedTableIndex(w[0]));
+            }
 
-        if let Some(to_indices) = mov.to {
             let table_cards: Vec<&Card> = to_indices.iter()
-                .map(|&i| self.table.get(i))
-                .collect::<Option<Vec<&Card>>>()
-                .ok_or(MoveError::OutOfRangeOfTable)?;
+                .map(|&i| self.table.get(i).ok_or(MoveError::OutOfRangeOfTable { index: i, table_len: self.table.len() }))
+                .collect::<Result<Vec<&Card>, MoveError>>()?;
 
-            if hand_card.number == CardNum::Numeric(1) {
+            if self.rules.ace_sweeps(hand_card) {
                 // We have an ace, we get everything (including itself)
 
                 last_move = Some(Move {
                     card_played: hand_card,
-                    cards_taken: Some(self.table.iter().map(|c| c.clone()).collect()),
-                    turn: self.turn,
+                    cards_taken: Some(self.table.iter().copied().collect()),
+                    seat, team,
                 });
 
+                let swept_something = !self.table.is_empty();
                 for _ in 0..self.table.len() {
                     player.pile.push_to_top(self.table.take_from_top().unwrap());
                 }
@@ -243,45 +513,69 @@ impl Match {
                 // Remove it from hand
                 remove_elem_from_vec(&mut player.curr_hand, hand_card);
 
-            } else if hand_card.value() == table_cards.iter().map(|c| c.value()).sum() {
+                let last_play = self.deck.is_empty() && others_empty && player.curr_hand.is_empty();
+                if swept_something && self.rules.ace_sweep_scopa && (self.rules.last_play_scopa || !last_play) {
+                    player.scope += 1;
+                }
+
+            } else if takes(hand_card, &table_cards, &self.rules) {
+                let by_value = hand_card.value() == table_cards.iter().map(|c| c.value()).sum::<usize>();
+                if self.rules.capture_priority && by_value && table_cards.len() > 1 {
+                    if let Some(single) = self.table.iter().find(|c| c.value() == hand_card.value()) {
+                        return Err(MoveError::MustTakeSingleCard(*single));
+                    }
+                }
+
                 for card in &table_cards {
                     player.pile.push_to_top(**card);
-                    player.pile.push_to_top(hand_card);
                 }
+                player.pile.push_to_top(hand_card);
                 last_move = Some(Move {
                     card_played: hand_card,
-                    cards_taken: Some(table_cards.iter().map(|&c| c.clone()).collect()),
-                    turn: self.turn,
+                    cards_taken: Some(table_cards.iter().map(|&c| *c).collect()),
+                    seat, team,
                 });
 
                 for i in to_indices.into_iter().rev() { self.table.remove(i); } // Remove them from the table
                 remove_elem_from_vec(&mut player.curr_hand, hand_card);
 
-                if self.table.is_empty() { // Do we have a scopa (non-ace)?
+                let last_play = self.deck.is_empty() && others_empty && player.curr_hand.is_empty();
+                if self.table.is_empty() && (self.rules.last_play_scopa || !last_play) { // Do we have a scopa (non-ace)?
                     player.scope += 1;
                 }
 
             } else {
-                return Err(MoveError::MismatchedValues);
+                return Err(MoveError::MismatchedValues { card: hand_card, taken: table_cards.into_iter().copied().collect() });
             }
         } else {
+            if self.rules.must_capture && !self.table.is_empty() {
+                if let Some(capture) = captures_with(hand_card, &self.table, &self.rules).into_iter().next() {
+                    let capture = capture.into_iter().map(|i| self.table[i]).collect();
+                    return Err(MoveError::CouldCapture { card: hand_card, capture });
+                }
+            }
+
             // Place on table
-            // TODO: make asso piglia tutto do the thingy instead of placing it when placing a card on the table (`N;` vs `tN` should be identical with an ace)
             last_move = Some(Move {
                 card_played: hand_card,
                 cards_taken: None,
-                turn: self.turn,
+                seat, team,
             });
             self.table.push_to_top(hand_card);
             remove_elem_from_vec(&mut player.curr_hand, hand_card);
         }
 
+        self.history.extend(last_move.clone().map(Event::Played));
+        if let Some(before) = before {
+            self.undo_stack.push(before);
+            self.redo_stack.clear();
+        }
+
         if player.curr_hand.is_empty() && !self.deck.is_empty() {
-            // Redeal three cards from the deck
-            for _ in 0..3 {
-                let c = self.deck.take_from_top().unwrap();
-                player.curr_hand.push(c);
-            }
+            // Redeal a new hand from the deck
+            player.curr_hand = self.deck.draw(self.rules.hand_size);
+            self.history.push(Event::Dealt { seat, team, cards: player.curr_hand.clone() });
+            self.declare(seat);
         }
         Ok(last_move)
     }
@@ -289,81 +583,208 @@ impl Match {
     fn tally_final_points(&self) -> PointTally {
         let mut tally = PointTally::default();
 
-        let fir = &self.player_first.pile;
-        let shuf = &self.player_shuffler.pile;
+        let teams: Vec<Team> = self.rules.all_teams().collect();
+        let piles: Vec<Deck> = teams.iter().map(|&t| self.pile_of(t)).collect();
+        let who_has = |card: Card| teams.iter().copied().find(|t| piles[t.0].contains(&card));
 
-        tally.scope_first = self.player_first.scope;
-        tally.scope_shuf = self.player_shuffler.scope;
+        tally.scope = teams.iter().map(|&t| self.scope_of(t)).collect();
 
         // Number of cards
-        match fir.len().cmp(&shuf.len()) {
-            Ordering::Greater  => tally.num_cards = Some(Turn::First),
-            Ordering::Equal    => tally.num_cards = None,
-            Ordering::Less     => tally.num_cards = Some(Turn::Shuffler),
-        }
+        tally.num_cards = most(&piles.iter().map(|p| p.len()).collect::<Vec<usize>>());
 
         // Number of Denari (monee monee monee)
-        match fir.iter().filter(|c| c.suit == Suit::Denari).count()
-            .cmp(&shuf.iter().filter(|c| c.suit == Suit::Denari).count()) {
-            Ordering::Greater  => tally.num_denari = Some(Turn::First),
-            Ordering::Equal    => tally.num_denari = None,
-            Ordering::Less     => tally.num_denari = Some(Turn::Shuffler),
-        }
+        tally.num_denari = most(&piles.iter()
+            .map(|p| p.iter().filter(|c| c.suit == Suit::Denari).count())
+            .collect::<Vec<usize>>());
 
         // Who has 7 bello
-        if fir.iter().any(|c| c == &Card::denari(7)) {
-            tally.sette_bello = Turn::First;
-        } else {
-            tally.sette_bello = Turn::Shuffler;
-        }
+        tally.sette_bello = who_has(Card::denari(7));
 
         // Who has king bello
-        if fir.iter().any(|c| c == &Card::denari(10 /* Re */)) {
-            tally.re_bello = Turn::First;
-        } else {
-            tally.re_bello = Turn::Shuffler;
+        if self.rules.re_bello {
+            tally.re_bello = who_has(Card::denari(10 /* Re */));
         }
 
-        // Napoli 
-        if let Some(p) = check_napoli(fir) {
-            tally.napoli = Some((Turn::First, p));
-        } else if let Some(p) = check_napoli(shuf) {
-            tally.napoli = Some((Turn::Shuffler, p));
-        } else {
-            tally.napoli = None;
+        // Napoli (only one team can have the 1, 2 and 3)
+        if self.rules.napoli {
+            tally.napoli = teams.iter().find_map(|&t| check_napoli(&piles[t.0]).map(|p| (t, p)));
         }
 
-        // Primiera (7s thing (just counting))
-        let mut i = 7;
-        while i > 0 {
-            match cards_with_value(i, fir).cmp(&cards_with_value(i, shuf)) {
-                Ordering::Greater => {
-                    tally.primiera = Some(Turn::First);
-                    break;
-                },
-                Ordering::Equal   => i -= 1,
-                Ordering::Less    => {
-                    tally.primiera = Some(Turn::Shuffler);
-                    break;
-                }
-            }
+        // Grande and piccola (Cirulla's figures and low denari)
+        if self.rules.grande_piccola {
+            tally.grande = teams.iter().copied().find(|&t| [8, 9, 10].iter().all(|&i| piles[t.0].contains(&Card::denari(i))));
+            tally.piccola = teams.iter().find_map(|&t| check_piccola(&piles[t.0]).map(|p| (t, p)));
         }
-        
+
+        // Primiera: best card of each suit, higher total wins (having all four suits is a must)
+        tally.primiera_scores = piles.iter().map(primiera).collect();
+        if tally.primiera_scores.iter().any(Option::is_some) {
+            tally.primiera = most(&tally.primiera_scores);
+        }
+
         tally
     }
 
-    fn parse_move(mov: &str) -> Result<ParsedMove, MoveError> {
-        let (_, result) = parse_move_internal(mov).map_err(MoveError::ParseError)?;
-        Ok(result)
+    /// Takes the last move back: hands, table, piles, scope and turn go back to how they
+    /// were right before it
+    pub fn undo(&mut self) -> Result<(), UndoError> {
+        if !self.rules.allow_undo { return Err(UndoError::Disabled) }
+        let before = self.undo_stack.pop().ok_or(UndoError::NothingToUndo)?;
+        self.swap_with(before, |m| &mut m.redo_stack);
+        Ok(())
+    }
+
+    /// Plays again a move taken back with [`Match::undo`] (until a new move is made)
+    pub fn redo(&mut self) -> Result<(), UndoError> {
+        if !self.rules.allow_undo { return Err(UndoError::Disabled) }
+        let after = self.redo_stack.pop().ok_or(UndoError::NothingToRedo)?;
+        self.swap_with(after, |m| &mut m.undo_stack);
+        Ok(())
     }
+
+    /// Becomes `other` (keeping the undo/redo stacks), the current state gets pushed to `stack`
+    fn swap_with(&mut self, other: Match, stack: fn(&mut Match) -> &mut Vec<Match>) {
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let current = std::mem::replace(self, other);
+        self.undo_stack = undo_stack;
+        self.redo_stack = redo_stack;
+        stack(self).push(current);
+    }
+
+    /// A copy of the match, without the undo/redo stacks
+    fn snapshot(&self) -> Match {
+        Match {
+            seat: self.seat,
+            first: self.first,
+            players: self.players.clone(),
+            deck: self.deck.clone(),
+            table: self.table.clone(),
+            rules: self.rules,
+            history: self.history.clone(),
+            undo_stack: vec![],
+            redo_stack: vec![],
+        }
+    }
+
+    /// What the player whose turn it is gets to see
+    pub fn view(&self) -> PlayerView<'_> {
+        self.view_of(self.seat)
+    }
+
+    /// What `seat` gets to see, whether it's their turn or not (not even partners see each other's hands)
+    pub fn view_of(&self, seat: usize) -> PlayerView<'_> {
+        PlayerView {
+            seat,
+            team: self.rules.team_of(seat),
+            hand: &self.players[seat].curr_hand,
+            table: &self.table,
+            piles: self.rules.all_teams().map(|t| self.pile_of(t)).collect(),
+            scope: self.rules.all_teams().map(|t| self.scope_of(t)).collect(),
+            hand_lens: self.players.iter().map(|p| p.curr_hand.len()).collect(),
+            shown: (0..self.players.len()).map(|s| self.shown_by(s)).collect(),
+            deck_len: self.deck.len(),
+            last_taker: None,
+            rules: self.rules,
+        }
+    }
+
+    /// Cards of `seat`'s hand that were shown for a declaration, see [`PlayerView::shown`]
+    fn shown_by(&self, seat: usize) -> Vec<Card> {
+        let hand = &self.players[seat].curr_hand;
+        self.history.iter()
+            .filter_map(|e| match e {
+                Event::Bonus { seat: s, bonus: Bonus::Busso | Bonus::Decino, cards, .. } if *s == seat => Some(cards),
+                _                                                                                  => None,
+            })
+            .flatten()
+            .filter(|c| hand.contains(c))
+            .copied()
+            .collect()
+    }
+
+    /// The moves made so far this match (the plays in [`Match::history`]), oldest first
+    pub fn moves(&self) -> impl DoubleEndedIterator<Item = &Move> + Clone {
+        self.history.iter().filter_map(|e| match e {
+            Event::Played(m) => Some(m),
+            _                => None,
+        })
+    }
+
+    /// The [`Action`] that would make `mov` right now, if it's a move the current player could try
+    pub fn action_for(&self, mov: &Move) -> Option<Action> {
+        if mov.seat != self.seat { return None }
+
+        let view = self.view();
+        let hand = view.hand.iter().position(|&c| c == mov.card_played)?;
+        match &mov.cards_taken {
+            None        => Some(Action::Drop { hand }),
+            Some(taken) => {
+                let table = taken.iter()
+                    .map(|c| view.table.iter().position(|t| t == c))
+                    .collect::<Option<Vec<usize>>>()?;
+                Some(Action::Capture { hand, table })
+            },
+        }
+    }
+
+    /// Every move the player whose turn it is can make right now, see [`PlayerView::legal_moves`]
+    pub fn legal_moves(&self) -> Vec<Action> {
+        self.view().legal_moves()
+    }
+
+    /// Cards can be written by index or by name, named ones get looked up in the current hand and table
+    fn parse_move(&self, mov: &str) -> Result<Action, MoveError> {
+        let (_, result) = nom::combinator::all_consuming(parse_move_internal)(mov.trim())
+            .map_err(|e| MoveError::ParseError {
+                input: mov.trim().to_string(),
+                rest: match e {
+                    nom::Err::Error(e) | nom::Err::Failure(e) => e.input.to_string(),
+                    nom::Err::Incomplete(_)                   => String::new(),
+                },
+            })?;
+        result.resolve(&self.view())
+    }
+}
+
+/// Ace, 2 and 3 of denari are 3 points, each denari card following them one more (up to the 6)
+fn check_piccola(pila: &Deck) -> Option<usize> {
+    if ![1, 2, 3].iter().all(|&i| pila.contains(&Card::denari(i))) { return None }
+
+    Some(3 + (4..=6).take_while(|&i| pila.contains(&Card::denari(i))).count())
 }
 
 pub fn has_full_napoli(pila: &Deck) -> bool {
     (1..=10).all(|i| pila.contains(&Card::denari(i)))
 }
 
-fn cards_with_value(target_value: usize, cards: &Deck) -> usize {
-    cards.iter().filter(|c| c.value() == target_value).count()
+/// What a card is worth for the primiera: 7 is 21, 6 is 18, ace 16, 5 is 15, 4 is 14,
+/// 3 is 13, 2 is 12 and figures 10
+pub fn primiera_value(card: &Card) -> usize {
+    match card.number {
+        CardNum::Numeric(7) => 21,
+        CardNum::Numeric(6) => 18,
+        CardNum::Numeric(1) => 16,
+        CardNum::Numeric(n) => 10 + n,
+        CardNum::Fante | CardNum::Cavallo | CardNum::Re => 10,
+    }
+}
+
+/// Primiera score of a pile: its best card of each suit, added up. `None` if some suit is
+/// missing altogether, as then you can't have a primiera
+pub fn primiera(pila: &Deck) -> Option<usize> {
+    [Suit::Denari, Suit::Coppe, Suit::Bastoni, Suit::Spade].iter()
+        .map(|&suit| pila.iter().filter(|c| c.suit == suit).map(primiera_value).max())
+        .sum()
+}
+
+/// The team with more than any other, `None` if there's a tie at the top
+fn most<T: Ord>(by_team: &[T]) -> Option<Team> {
+    let best = by_team.iter().max()?;
+    match by_team.iter().filter(|&x| x == best).count() {
+        1 => by_team.iter().position(|x| x == best).map(Team),
+        _ => None,
+    }
 }
 
 fn check_napoli(pila: &Deck) -> Option<usize> {
@@ -386,37 +807,252 @@ fn remove_elem_from_vec<T>(v: &mut Vec<T>, elem: T) where T: PartialEq {
     v.remove(index);
 }
 
-struct ParsedMove {
-    from: usize,
-    to: Option<Vec<usize>>
+/// A move with its cards already resolved to indices (what the notation parses into)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    /// Play the hand card at `hand`, taking the table cards at `table` (`hand;t1+t2+...`)
+    Capture { hand: usize, table: Vec<usize> },
+    /// Place the hand card at `hand` on the table (`thand`)
+    Drop { hand: usize },
+}
+
+impl Action {
+    pub fn hand_index(&self) -> usize {
+        match self {
+            Action::Capture { hand, .. } => *hand,
+            Action::Drop { hand }        => *hand,
+        }
+    }
+}
+
+/// Everything the player whose turn it is can see: their own hand, the table and every
+/// team's pile, but only how many cards everyone else holds and how many are left in the deck
+#[derive(Clone, Debug)]
+pub struct PlayerView<'a> {
+    pub seat: usize,
+    /// `seat`'s team
+    pub team: Team,
+    pub hand: &'a [Card],
+    pub table: &'a Deck,
+    /// What each team has taken, partners' cards together
+    pub piles: Vec<Deck>,
+    /// Each team's scope
+    pub scope: Vec<usize>,
+    /// How many cards each seat holds, `seat` included
+    pub hand_lens: Vec<usize>,
+    /// Cards each seat showed for a busso or decino (see [`Rules::declarations`]) and still
+    /// holds: everyone at the table has seen them
+    pub shown: Vec<Vec<Card>>,
+    pub deck_len: usize,
+    /// Seat whose team gets the leftover table at the end, if known (the match itself doesn't track it, see [`Game::view`])
+    pub last_taker: Option<usize>,
+    pub rules: Rules,
+}
+
+impl PlayerView<'_> {
+    /// Every move [`Match::play`] would accept: each hand card can be dropped on the table
+    /// or take any set of table cards adding up to its value, as far as [`Rules`] allow.
+    /// An ace takes everything, so it only gets the one move: the capture (no table indices,
+    /// as in `a;`), or the drop if it stays on an empty table
+    pub fn legal_moves(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        for (hand, card) in self.hand.iter().enumerate() {
+            if self.rules.ace_sweeps(*card) {
+                actions.push(match self.table.is_empty() && self.rules.ace_on_empty_table == AceOnEmptyTable::Stays {
+                    true  => Action::Drop { hand },
+                    false => Action::Capture { hand, table: vec![] },
+                });
+                continue;
+            }
+
+            let captures = captures_with(*card, self.table, &self.rules);
+            let takes_something = !captures.is_empty() && !self.table.is_empty();
+
+            if !(self.rules.must_capture && takes_something) {
+                actions.push(Action::Drop { hand });
+            }
+            actions.extend(captures.into_iter().map(|table| Action::Capture { hand, table }));
+        }
+        actions
+    }
+}
+
+/// Sets of table indices `card` could take (an ace's is always the empty set, as it takes everything)
+fn captures_with(card: Card, table: &Deck, rules: &Rules) -> Vec<Vec<usize>> {
+    if rules.ace_sweeps(card) {
+        return vec![vec![]];
+    }
+
+    let single_only = rules.capture_priority
+        && table.iter().any(|c| c.value() == card.value());
+    let mut captures = Vec::new();
+    for subset in 1..(1usize << table.len()) {
+        let indices: Vec<usize> = (0..table.len()).filter(|i| subset & (1 << i) != 0).collect();
+        let sum = indices.iter().map(|&i| table[i].value()).sum::<usize>();
+        // Capture priority is about taking by value, the 15 is always there
+        let by_value = sum == card.value() && !(single_only && indices.len() > 1);
+        if by_value || (rules.capture_fifteen && sum + card.value() == 15) {
+            captures.push(indices);
+        }
+    }
+    captures
 }
 
+/// Whether `card` can take `taken`: they add up to its value or, with [`Rules::capture_fifteen`],
+/// to 15 together with it
+fn takes(card: Card, taken: &[&Card], rules: &Rules) -> bool {
+    let sum = taken.iter().map(|c| c.value()).sum::<usize>();
+    sum == card.value() || (rules.capture_fifteen && !taken.is_empty() && sum + card.value() == 15)
+}
+
+/// What the starting `table` is worth, see [`Rules::table_bonus`]. The matta can be anything
+/// from 1 to 10, so it counts for 30 if it can
+fn table_bonus(table: &Deck, rules: &Rules) -> Option<Bonus> {
+    if !rules.table_bonus || table.is_empty() { return None }
+
+    let sum = table.iter().filter(|&&c| !rules.is_matta(c)).map(|c| c.value()).sum::<usize>();
+    let sums: Vec<usize> = match table.iter().any(|&c| rules.is_matta(c)) {
+        true  => (1..=10).map(|v| sum + v).collect(),
+        false => vec![sum],
+    };
+    if sums.contains(&30) {
+        Some(Bonus::Thirty)
+    } else if sums.contains(&15) {
+        Some(Bonus::Fifteen)
+    } else {
+        None
+    }
+}
+
+/// What a fresh `hand` of three can be shown for, see [`Rules::declarations`]. The matta
+/// takes the others' value for a decino, or counts as an ace for a busso
+fn declaration(hand: &[Card], rules: &Rules) -> Option<Bonus> {
+    if !rules.declarations || hand.len() != 3 { return None }
+
+    let others: Vec<usize> = hand.iter().filter(|&&c| !rules.is_matta(c)).map(|c| c.value()).collect();
+    let matte = hand.len() - others.len();
+    if others.windows(2).all(|w| w[0] == w[1]) {
+        Some(Bonus::Decino)
+    } else if others.iter().sum::<usize>() + matte < 10 {
+        Some(Bonus::Busso)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// `input` isn't a move, and whatever went wrong starts at `rest`
+    ParseError { input: String, rest: String },
+    /// The cards taken don't add up to the value of the card played
+    MismatchedValues { card: Card, taken: Vec<Card> },
+    /// There's no card at `index` in a hand of `hand_len`
+    OutOfRangeOfHand { index: usize, hand_len: usize },
+    /// There's no card at `index` on a table of `table_len`
+    OutOfRangeOfTable { index: usize, table_len: usize },
+    /// The same table index was written more than once
+    RepeatedTableIndex(usize),
+    /// Took several cards while this one, on its own, matches the card played (see [`Rules::capture_priority`])
+    MustTakeSingleCard(Card),
+    /// Tried to place `card` on the table when it could have taken `capture` (see [`Rules::must_capture`])
+    CouldCapture { card: Card, capture: Vec<Card> },
+    /// The move names a card that isn't in the player's hand
+    NotInHand(Card),
+    /// The move names a card that isn't on the table
+    NotOnTable(Card),
+    /// Only the hand card was given, but it can take more than one thing: pick one of `options`
+    Ambiguous { card: Card, options: Vec<Action> },
+    /// Only the hand card was given, but it can't take anything: placing it has to be written out
+    NothingToTake(Card),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let cards = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join("+");
+        match self {
+            MoveError::ParseError { input, rest } if rest.is_empty() =>
+                write!(f, "'{input}' isn't a whole move, is something missing at the end?"),
+            MoveError::ParseError { input, rest } =>
+                write!(f, "couldn't read '{input}' as a move, the problem starts at '{rest}'"),
+            MoveError::MismatchedValues { card, taken } if taken.is_empty() =>
+                write!(f, "{card} has to take something (or be placed on the table with `t`)"),
+            MoveError::MismatchedValues { card, taken } =>
+                write!(f, "{card} is worth {}, but {} add up to {}",
+                       card.value(), cards(taken), taken.iter().map(|c| c.value()).sum::<usize>()),
+            MoveError::OutOfRangeOfHand { index, hand_len } =>
+                write!(f, "there's no card {index} in your hand, it goes from 0 to {}", hand_len.saturating_sub(1)),
+            MoveError::OutOfRangeOfTable { index, table_len: 0 } =>
+                write!(f, "there's no card {index} on the table, it's empty"),
+            MoveError::OutOfRangeOfTable { index, table_len } =>
+                write!(f, "there's no card {index} on the table, it goes from 0 to {}", table_len - 1),
+            MoveError::RepeatedTableIndex(i) =>
+                write!(f, "table card {i} is written more than once"),
+            MoveError::MustTakeSingleCard(card) =>
+                write!(f, "{card} is worth the same as the card played, so it has to be taken on its own"),
+            MoveError::CouldCapture { card, capture } =>
+                write!(f, "{card} can take {}, so it can't be placed on the table", cards(capture)),
+            MoveError::NotInHand(card) =>
+                write!(f, "{card} isn't in your hand"),
+            MoveError::NotOnTable(card) =>
+                write!(f, "{card} isn't on the table"),
+            MoveError::Ambiguous { card, options } =>
+                write!(f, "{card} can take more than one thing, write which: {}",
+                       options.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(" or ")),
+            MoveError::NothingToTake(card) =>
+                write!(f, "{card} can't take anything, place it on the table with `t`"),
+        }
+    }
+}
+impl std::error::Error for MoveError {}
+
+#[cfg(feature = "serde")]
 #[derive(Debug)]
-pub enum MoveError<'a> {
-    /// Move could not be parsed
-    ParseError(nom::Err<nom::error::Error<&'a str>>),
-    /// Index isn't a base 10 single-char digit
-    InvalidDigit,
-    /// When you try to play an ace but you don't have it
-    InvalidAce,  
-    /// Values don't match
-    MismatchedValues,
-    /// An addition did not yield the expected value
-    AdditionDoesntCheckOut,
-    /// The hand index was invalid
-    OutOfRangeOfHand,
-    /// At least one of the table indices was out of range 
-    OutOfRangeOfTable,
-}
-
-impl Display for Turn {
+pub enum SaveError {
+    /// Couldn't read or write the file
+    Io(std::io::Error),
+    /// The file isn't a saved game (or it's from an incompatible version)
+    Format(serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl Display for SaveError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
-            Turn::First    => write!(f, "First"),
-            Turn::Shuffler => write!(f, "Shuffler"),
+            SaveError::Io(e)     => write!(f, "could not access the save file: {e}"),
+            SaveError::Format(e) => write!(f, "not a valid saved game: {e}"),
         }
     }
 }
+#[cfg(feature = "serde")]
+impl std::error::Error for SaveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoError {
+    /// The rules don't allow taking moves back (see [`Rules::allow_undo`])
+    Disabled,
+    /// No moves made yet this match
+    NothingToUndo,
+    /// Nothing was undone since the last move
+    NothingToRedo,
+}
+
+impl Display for UndoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            UndoError::Disabled      => write!(f, "undo is disabled for this game"),
+            UndoError::NothingToUndo => write!(f, "there's no move to undo this match"),
+            UndoError::NothingToRedo => write!(f, "there's no move to redo"),
+        }
+    }
+}
+impl std::error::Error for UndoError {}
+
+impl Display for Team {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{0}[38;5;{1}m{2}{0}[0m", 27 as char, self.color(), self.name())
+    }
+}
 
 impl Display for Match {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -425,14 +1061,14 @@ impl Display for Match {
 "-------------------
 Turn: '{}'
 Deck has '{}' cards ('{}' turns left)
-First has '{}' cards
-Shuffler has '{}' cards
+{}
 Table has cards: '{}'
 -------------------",
-            self.turn,
-            self.deck.len(), self.deck.len() / 6,
-            self.player_first.curr_hand.len(),
-            self.player_shuffler.curr_hand.len(),
+            seat_name(self.seat, self.team()),
+            self.deck.len(), self.deck.len() / (self.players.len() * self.rules.hand_size).max(1),
+            self.players.iter().enumerate()
+                .map(|(seat, p)| format!("{} has '{}' cards", seat_name(seat, self.rules.team_of(seat)), p.curr_hand.len()))
+                .collect::<Vec<String>>().join("\n"),
             self.table.iter().enumerate().map(|(i, c)| format!("{c}({i})")).collect::<Vec<String>>().join(" ; "),
         );
         write!(f, "{s}")
@@ -440,56 +1076,44 @@ Table has cards: '{}'
 }
 
 
-impl Turn {
-    fn toggle_turn(&mut self) {
-        match self {
-            Self::First => *self = Self::Shuffler,
-            Self::Shuffler => *self = Self::First,
-        }
-    }
-}
-
-impl Display for PlayerKind {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        match self {
-            PlayerKind::Purple => write!(f, "{}", purple_text()),
-            PlayerKind::Green  => write!(f, "{}", green_text()),
-        }
-    }
-}
-
-impl std::ops::Not for PlayerKind {
-    type Output = Self;
-
-    fn not(self) -> Self {
-        match self {
-            Self::Purple => Self::Green,
-            Self::Green  => Self::Purple,
-        }
+/// How a seat gets called: by its team, the second player of a team is the partner
+pub fn seat_name(seat: usize, team: Team) -> String {
+    match seat == team.0 {
+        true  => team.name(),
+        false => format!("{}'s partner", team.name()),
     }
 }
 
 
 impl Display for PointTally {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let who = |team: Option<Team>| team.map(Team::name).unwrap_or("Nobody".to_string());
+
+        writeln!(f, "=================")?;
+        for (team, scope) in self.scope.iter().enumerate() {
+            writeln!(f, "{}'s scope:\t\t\t{},", Team(team).name(), scope)?;
+        }
         write!(f,
-               "=================
-First's scope:\t\t\t{},
-Shuf's scope:\t\t\t{},
-Nº cards:\t\t\t{},
+               "Nº cards:\t\t\t{},
 Nº denari\t\t\t{},
 Sette bello:\t\t\t{},
 Re bello:\t\t\t{},
 Napoli:  \t\t\t{},
-Primiera:\t\t\t{}
+Grande:  \t\t\t{},
+Piccola: \t\t\t{},
+Primiera:\t\t\t{} ({})
 =================",
-               self.scope_first, self.scope_shuf,
-               self.num_cards.map(|n| n.to_string()).unwrap_or("Nobody".to_string()),
-               self.num_denari.map(|n| n.to_string()).unwrap_or("Nobody".to_string()),
-               self.sette_bello,
-               self.re_bello,
-               self.napoli.map(|(t, n)| format!("{t} ({n})")).unwrap_or("Nobody".to_string()),
-               self.primiera.map(|n| n.to_string()).unwrap_or("Nobody".to_string()),
+               who(self.num_cards),
+               who(self.num_denari),
+               who(self.sette_bello),
+               who(self.re_bello),
+               self.napoli.map(|(t, n)| format!("{} ({n})", t.name())).unwrap_or("Nobody".to_string()),
+               who(self.grande),
+               self.piccola.map(|(t, n)| format!("{} ({n})", t.name())).unwrap_or("Nobody".to_string()),
+               who(self.primiera),
+               self.primiera_scores.iter()
+                   .map(|p| p.map(|n| n.to_string()).unwrap_or("-".to_string()))
+                   .collect::<Vec<String>>().join(" vs "),
         )
     }
 }
@@ -498,28 +1122,364 @@ impl Default for Match {
     fn default() -> Self { Self::new() }
 }
 impl Default for Game {
-    fn default() -> Self { Self::new() }
+    fn default() -> Self { Self::new(Rules::default()) }
 }
 
 
-pub fn purple_text() -> String {
-    format!("{0}[38;5;93mPurple{0}[0m", 27 as char)
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Action::Drop { hand }           => write!(f, "t{hand}"),
+            Action::Capture { hand, table } => write!(f, "{hand};{}",
+                table.iter().map(|i| i.to_string()).collect::<Vec<String>>().join("+")),
+        }
+    }
 }
-pub fn green_text() -> String {
-    format!("{0}[38;5;34mGreen{0}[0m", 27 as char)
+
+impl Display for Bonus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Bonus::Fifteen => write!(f, "15 on the table"),
+            Bonus::Thirty  => write!(f, "30 on the table"),
+            Bonus::Busso   => write!(f, "a busso"),
+            Bonus::Decino  => write!(f, "a decino"),
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let cards = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+        match self {
+            Event::Played(m)                             => write!(f, "{m}"),
+            Event::Dealt { seat, team, cards: c }        => write!(f, "{} was dealt {}", seat_name(*seat, *team), cards(c)),
+            Event::TableSwept { team, cards: c }         => write!(f, "{} got the rest of the table: {}", team.name(), cards(c)),
+            Event::Bonus { seat, team, bonus, cards: c } => write!(f, "{} scored {} ({} scope): {}", seat_name(*seat, *team), bonus, bonus.scope(), cards(c)),
+        }
+    }
 }
 
 impl Display for Move {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match &self.cards_taken {
             None => write!(f, "{} placed {} on the table",
-                           self.turn,
+                           seat_name(self.seat, self.team),
                            self.card_played),
+            // An ace on an empty table, see [`AceOnEmptyTable::Taken`]
+            Some(tables) if tables.is_empty() => write!(f, "{} took {} by itself",
+                                                        seat_name(self.seat, self.team),
+                                                        self.card_played),
             Some(tables) => write!(f, "{} took {} with {}",
-                                   self.turn,
+                                   seat_name(self.seat, self.team),
                                    tables.iter().map(|c| c.to_string()).collect::<Vec<String>>().join("+"),
                                    self.card_played),
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(n: usize) -> Card { Card::new(Suit::Denari, n) }
+    fn c(n: usize) -> Card { Card::new(Suit::Coppe, n) }
+    fn b(n: usize) -> Card { Card::new(Suit::Bastoni, n) }
+    fn s(n: usize) -> Card { Card::new(Suit::Spade, n) }
+
+    fn deck(cards: &[Card]) -> Deck {
+        cards.iter().copied().collect()
+    }
+
+    fn cards(deck: &Deck) -> Vec<Card> {
+        deck.iter().copied().collect()
+    }
+
+    /// Two players in the middle of a match of `rules`: seat 0 plays `hand` on `table`, and
+    /// there's still a card in the deck so nothing is the last play
+    fn position(rules: Rules, hand: &[Card], table: &[Card]) -> Match {
+        let mut players = vec![Player::default(); 2];
+        players[0].curr_hand = hand.to_vec();
+        players[1].curr_hand = vec![s(10)];
+        Match {
+            seat: 0,
+            first: 0,
+            players,
+            deck: deck(&[b(10)]),
+            table: deck(table),
+            rules,
+            history: vec![],
+            undo_stack: vec![],
+            redo_stack: vec![],
+        }
+    }
+
+    #[test]
+    fn primiera_adds_up_the_best_card_of_each_suit() {
+        assert_eq!(primiera(&deck(&[d(7), c(6), b(1), s(5)])), Some(21 + 18 + 16 + 15));
+        assert_eq!(primiera(&deck(&[d(7), d(2), c(10), b(3), b(9), s(4)])), Some(21 + 10 + 13 + 14));
+        assert_eq!(primiera(&deck(&[d(7), c(7), b(7), d(6)])), None);
+    }
+
+    #[test]
+    fn primiera_needs_all_four_suits() {
+        // Three sevens lose to four twos when there's no spade to go with them
+        let mut m = position(Rules::classic(), &[], &[]);
+        m.players[0].pile = deck(&[d(2), c(2), b(2), s(2)]);
+        m.players[1].pile = deck(&[d(7), c(7), b(7), d(6)]);
+        let tally = m.tally_final_points();
+        assert_eq!(tally.primiera, Some(Team(0)));
+        assert_eq!(tally.primiera_of(Team(0)), Some(48));
+        assert_eq!(tally.primiera_of(Team(1)), None);
+
+        // Nobody gets it on a tie
+        m.players[0].pile = deck(&[d(7), c(2), b(2), s(2)]);
+        m.players[1].pile = deck(&[c(7), d(2), b(2), s(2)]);
+        assert_eq!(m.tally_final_points().primiera, None);
+    }
+
+    #[test]
+    fn capture_priority() {
+        let (hand, table) = ([d(7)], [c(3), s(4), c(7)]);
+        let mut m = position(Rules::classic(), &hand, &table);
+        assert_eq!(m.play(&Action::Capture { hand: 0, table: vec![0, 1] }).unwrap_err(), MoveError::MustTakeSingleCard(c(7)));
+        m.play(&Action::Capture { hand: 0, table: vec![2] }).unwrap();
+        assert_eq!(cards(&m.table), vec![c(3), s(4)]);
+
+        let mut m = position(Rules { capture_priority: false, ..Rules::classic() }, &hand, &table);
+        m.play(&Action::Capture { hand: 0, table: vec![0, 1] }).unwrap();
+        assert_eq!(cards(&m.table), vec![c(7)]);
+    }
+
+    #[test]
+    fn must_capture() {
+        let mut m = position(Rules::classic(), &[d(5), d(6)], &[c(5), s(2)]);
+        assert_eq!(m.play(&Action::Drop { hand: 0 }).unwrap_err(), MoveError::CouldCapture { card: d(5), capture: vec![c(5)] });
+        m.play(&Action::Drop { hand: 1 }).unwrap(); // Nothing there adds up to 6
+
+        let mut m = position(Rules { must_capture: false, ..Rules::classic() }, &[d(5)], &[c(5), s(2)]);
+        m.play(&Action::Drop { hand: 0 }).unwrap();
+        assert_eq!(cards(&m.table), vec![c(5), s(2), d(5)]);
+    }
+
+    #[test]
+    fn ace_sweeps_however_its_written() {
+        for input in ["0;", "t0", "0;1"] {
+            let mut m = position(Rules::scopa_d_assi(), &[d(1)], &[c(5), s(3)]);
+            let mov = m.make_move(input).unwrap().unwrap();
+            assert_eq!(mov.cards_taken, Some(vec![c(5), s(3)]), "{input}");
+            assert!(m.table.is_empty());
+            assert_eq!(m.players[0].pile.len(), 3);
+        }
+        // Without asso piglia tutto `t0` is a plain drop
+        let mut m = position(Rules::classic(), &[d(1)], &[c(5), s(3)]);
+        m.make_move("t0").unwrap();
+        assert_eq!(cards(&m.table), vec![c(5), s(3), d(1)]);
+    }
+
+    #[test]
+    fn ace_sweep_scopa() {
+        let mut m = position(Rules::scopa_d_assi(), &[d(1)], &[c(5)]);
+        m.make_move("0;").unwrap();
+        assert_eq!(m.players[0].scope, 0);
+
+        let mut m = position(Rules { ace_sweep_scopa: true, ..Rules::scopa_d_assi() }, &[d(1)], &[c(5)]);
+        m.make_move("0;").unwrap();
+        assert_eq!(m.players[0].scope, 1);
+    }
+
+    #[test]
+    fn ace_on_an_empty_table() {
+        // Seat 1 plays the ace, seat 0 took last before that
+        let game_with = |ace_on_empty_table| {
+            let rules = Rules { ace_on_empty_table, ..Rules::scopa_d_assi() };
+            let mut game = Game::from_seed(0, rules);
+            game.curr_match = position(rules, &[s(10)], &[]);
+            game.curr_match.seat = 1;
+            game.curr_match.players[1].curr_hand = vec![d(1)];
+            game
+        };
+
+        let mut game = game_with(AceOnEmptyTable::Stays);
+        assert_eq!(game.make_move("0;").unwrap().unwrap().cards_taken, None);
+        assert_eq!(cards(&game.curr_match.table), vec![d(1)]);
+        assert_eq!(game.last_taker, 0);
+
+        let mut game = game_with(AceOnEmptyTable::Taken);
+        assert_eq!(game.make_move("t0").unwrap().unwrap().cards_taken, Some(vec![]));
+        assert!(game.curr_match.table.is_empty());
+        assert_eq!(cards(&game.curr_match.players[1].pile), vec![d(1)]);
+        assert_eq!(game.curr_match.players[1].scope, 0);
+        assert_eq!(game.last_taker, 1);
+    }
+
+    #[test]
+    fn fifteen_captures() {
+        // 6 takes 5+4 since together they make 15, and that clears the table: scopa
+        let mut m = position(Rules::cirulla(), &[d(6)], &[c(5), s(4)]);
+        m.play(&Action::Capture { hand: 0, table: vec![0, 1] }).unwrap();
+        assert!(m.table.is_empty());
+        assert_eq!(m.players[0].scope, 1);
+        assert_eq!(m.players[0].pile.len(), 3);
+
+        let mut m = position(Rules::cirulla(), &[d(6)], &[c(5), s(4), b(2)]);
+        m.play(&Action::Capture { hand: 0, table: vec![0, 1] }).unwrap();
+        assert_eq!(cards(&m.table), vec![b(2)]);
+        assert_eq!(m.players[0].scope, 0);
+
+        // 15 or the card's own value, nothing in between
+        let mut m = position(Rules::cirulla(), &[d(6)], &[c(5), s(3)]);
+        assert!(matches!(m.play(&Action::Capture { hand: 0, table: vec![0, 1] }), Err(MoveError::MismatchedValues { .. })));
+        // and only in Cirulla
+        let mut m = position(Rules::classic(), &[d(6)], &[c(5), s(4)]);
+        assert!(matches!(m.play(&Action::Capture { hand: 0, table: vec![0, 1] }), Err(MoveError::MismatchedValues { .. })));
+    }
+
+    #[test]
+    fn table_bonuses() {
+        let cirulla = Rules::cirulla();
+        assert_eq!(table_bonus(&deck(&[d(5), c(5), s(3), b(2)]), &cirulla), Some(Bonus::Fifteen));
+        assert_eq!(table_bonus(&deck(&[d(10), c(10), s(7), b(3)]), &cirulla), Some(Bonus::Thirty));
+        assert_eq!(table_bonus(&deck(&[d(10), c(10), s(10), b(10)]), &cirulla), None);
+        assert_eq!(table_bonus(&deck(&[d(5), c(5), s(3), b(2)]), &Rules::classic()), None);
+
+        // The matta is worth whatever gets to 15 or 30
+        assert_eq!(table_bonus(&deck(&[c(7), d(10), s(2), b(1)]), &cirulla), Some(Bonus::Fifteen));
+        assert_eq!(table_bonus(&deck(&[c(7), d(10), s(10), b(3)]), &cirulla), Some(Bonus::Thirty));
+        assert_eq!(table_bonus(&deck(&[c(7), d(1), s(1), b(1)]), &cirulla), None);
+        assert_eq!(table_bonus(&deck(&[c(7), d(10), s(2), b(1)]), &Rules { matta: false, ..cirulla }), None);
+    }
+
+    #[test]
+    fn declarations() {
+        let cirulla = Rules::cirulla();
+        assert_eq!(declaration(&[d(1), c(2), s(4)], &cirulla), Some(Bonus::Busso));
+        assert_eq!(declaration(&[d(1), c(2), s(7)], &cirulla), None);
+        assert_eq!(declaration(&[d(4), c(4), s(4)], &cirulla), Some(Bonus::Decino));
+
+        // The matta copies the other two for a decino, or is an ace for a busso
+        assert_eq!(declaration(&[c(7), d(9), s(9)], &cirulla), Some(Bonus::Decino));
+        assert_eq!(declaration(&[c(7), d(2), s(5)], &cirulla), Some(Bonus::Busso));
+        assert_eq!(declaration(&[c(7), d(2), s(7)], &cirulla), None);
+        assert_eq!(declaration(&[c(7), d(9), s(9)], &Rules { matta: false, ..cirulla }), None);
+    }
+
+    #[test]
+    fn cirulla_deal_scores_its_bonuses() {
+        // Dealt from the top one card at a time (seat 0, seat 1, seat 0...), then the table
+        let dealt = [c(7), d(1), d(3), c(2), s(3), s(4), d(5), c(5), b(3), b(2)];
+        let mut stacked: Deck = Deck::standard_40().iter().copied().filter(|c| !dealt.contains(c)).collect();
+        for &card in dealt.iter().rev() {
+            stacked.push_to_top(card);
+        }
+        let m = Match::deal(stacked, Rules::cirulla(), 0);
+
+        // Seat 0 has the matta and two 3s, seat 1 has 1+2+4 and shuffled a table of 15
+        assert_eq!(m.players[0].curr_hand, vec![c(7), d(3), s(3)]);
+        assert_eq!(m.players[0].scope, Bonus::Decino.scope());
+        assert_eq!(m.players[1].scope, Bonus::Busso.scope() + Bonus::Fifteen.scope());
+        assert_eq!(m.players[1].pile.len(), 4);
+        assert!(m.table.is_empty());
+    }
+
+    #[test]
+    fn grande_and_piccola() {
+        assert_eq!(check_piccola(&deck(&[d(1), d(2)])), None);
+        assert_eq!(check_piccola(&deck(&[d(1), d(2), d(3)])), Some(3));
+        assert_eq!(check_piccola(&deck(&[d(1), d(2), d(3), d(4), d(5)])), Some(5));
+        assert_eq!(check_piccola(&deck(&[d(1), d(2), d(3), d(5)])), Some(3));
+        assert_eq!(check_piccola(&deck(&(1..=10).map(d).collect::<Vec<Card>>())), Some(6));
+
+        let mut m = position(Rules::cirulla(), &[], &[]);
+        m.players[0].pile = deck(&[d(1), d(2), d(3), d(4), d(8), d(9), d(10)]);
+        let tally = m.tally_final_points();
+        assert_eq!(tally.grande, Some(Team(0)));
+        assert_eq!(tally.piccola, Some((Team(0), 4)));
+    }
+
+    #[test]
+    fn every_legal_move_can_be_played() {
+        for name in Rules::PRESETS {
+            let rules = Rules::preset(name).unwrap().competitive();
+            for seed in 0..3 {
+                let mut game = Game::from_seed(seed, rules);
+                let mut turn = 0;
+                while game.matches_played < 2 {
+                    let legal = game.view().legal_moves();
+                    assert!(!legal.is_empty(), "{name}, seed {seed}: seat {} has no moves", game.seat_playing());
+                    for action in &legal {
+                        if let Err(e) = game.clone().play(action) {
+                            panic!("{name}, seed {seed}: {action} is a legal move, but playing it says {e}");
+                        }
+                    }
+                    game.play(&legal[turn * 7 % legal.len()]).unwrap();
+                    turn += 1;
+
+                    if let Some(tally) = game.is_match_over() {
+                        game.award_points(&tally);
+                        if game.winner().is_some() { break }
+                        game.next_match();
+                    } else {
+                        game.next_turn();
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        // Seat 0 takes the 7, then seat 1 clears the table with the 2 for a scopa
+        let mut game = Game::from_seed(0, Rules::default());
+        game.curr_match = position(Rules::default(), &[d(7), d(3)], &[c(7), b(2)]);
+        game.curr_match.players[1].curr_hand = vec![c(2), s(10)];
+        game.play(&Action::Capture { hand: 0, table: vec![0] }).unwrap();
+        game.next_turn();
+        game.play(&Action::Capture { hand: 0, table: vec![0] }).unwrap();
+        game.next_turn();
+        assert_eq!(game.last_taker, 1);
+
+        game.undo().unwrap();
+        let m = &game.curr_match;
+        assert_eq!(m.seat, 1);
+        assert_eq!(game.last_taker, 0);
+        assert_eq!(cards(&m.table), vec![b(2)]);
+        assert_eq!(m.players[0].curr_hand, vec![d(3)]);
+        assert_eq!(m.players[1].curr_hand, vec![c(2), s(10)]);
+        assert_eq!(m.players[0].pile.len(), 2);
+        assert!(m.players[1].pile.is_empty());
+        assert_eq!(m.players[1].scope, 0);
+
+        game.redo().unwrap();
+        let m = &game.curr_match;
+        assert_eq!(m.seat, 0);
+        assert_eq!(game.last_taker, 1);
+        assert!(m.table.is_empty());
+        assert_eq!(m.players[1].curr_hand, vec![s(10)]);
+        assert_eq!(m.players[1].pile.len(), 2);
+        assert_eq!(m.players[1].scope, 1);
+        assert_eq!(game.redo(), Err(UndoError::NothingToRedo));
+
+        // Back to the start, where there's nothing left to take back
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.curr_match.seat, 0);
+        assert_eq!(cards(&game.curr_match.table), vec![c(7), b(2)]);
+        assert_eq!(game.curr_match.players[0].curr_hand, vec![d(7), d(3)]);
+        assert_eq!(game.undo(), Err(UndoError::NothingToUndo));
+
+        // A new move throws away what was undone
+        game.play(&Action::Drop { hand: 1 }).unwrap();
+        assert_eq!(game.redo(), Err(UndoError::NothingToRedo));
+        game.undo().unwrap();
+        assert_eq!(game.curr_match.players[0].curr_hand, vec![d(7), d(3)]);
+
+        let rules = Rules::default().competitive();
+        let mut game = Game::from_seed(0, rules);
+        game.curr_match = position(rules, &[d(7)], &[c(7)]);
+        game.play(&Action::Capture { hand: 0, table: vec![0] }).unwrap();
+        assert_eq!(game.undo(), Err(UndoError::Disabled));
+        assert_eq!(game.redo(), Err(UndoError::Disabled));
+        assert!(game.curr_match.table.is_empty());
+    }
+}
@@ -1,10 +1,25 @@
 use scopa::*;
-use core::*;
-use std::io::stdin;
+#[path = "bin/common/mod.rs"]
+mod common;
+use common::*;
+use std::io::{stdin, BufReader};
+use std::net::TcpStream;
 
 fn main() {
+    if let Some(address) = arg_value("--connect") {
+        if let Err(e) = play_online(&address) {
+            eprintln!("Connection error: {e}");
+        }
+        return;
+    }
+
     let mut input = String::new();
-    let mut game = Game::new();
+    let rules = rules_from_args();
+    let mut game = match seed_from_args() {
+        Some(seed) => Game::from_seed(seed, rules),
+        None       => Game::new(rules),
+    };
+    let (mut bots, mut privacy, mut no_humans) = seating(&rules);
 
     println!(
 r#"Welcome to...
@@ -18,82 +33,283 @@ r#"Welcome to...
 The best game ever made, wooo
 
 
+Game seed: {} (pass `--seed {}` to play this exact game again)
+
 Press the Any button to begin...
-"#);
+"#, game.seed, game.seed);
 
-    stdin().read_line(&mut input).expect("Could not read from stdin");
+    if !no_humans {
+        stdin().read_line(&mut input).expect("Could not read from stdin");
+    }
     clear_term();
     loop {
+        if let Some(bot) = bots[game.team_playing().0].as_mut() {
+            let action = bot.choose(&game.view());
+            let move_made = game.play(&action).expect("Bots only pick moves that can be played");
+            println!("The bot just played: '{}'\n", move_made.map(|m| m.to_string()).unwrap_or("No previous move".to_string()));
 
-        println!("Current player is: '{}'", game.color_playing());
-        println!("Score is: {} '{}' - '{}' {}", purple_text(), game.purple_points, game.green_points, green_text());
+            if let Some(tally) = game.is_match_over() {
+                if end_match(&mut game, tally) { break }
+                if !no_humans {
+                    println!("Press any button to start the next match...");
+                    stdin().read_line(&mut input).expect("Could not read from stdin");
+                }
+            } else {
+                game.next_turn();
+            }
+            continue;
+        }
+
+        println!("Current player is: '{}'", who_plays(&game));
+        println!("Score is: {}", score_text(&game.points));
         println!("{}", game.curr_match);
         print!("You current cards are: ");
         game.print_cards_of_curr_player();
 
 
-        println!("Waiting for input now....");
-        stdin().read_line(&mut input).expect("Could not read from stdin");
+        println!("Waiting for input now.... (or `undo`, `redo`, `history`, `save <file>` / `load <file>`)");
+        input.clear();
+        if stdin().read_line(&mut input).expect("Could not read from stdin") == 0 {
+            break; // stdin was closed, nobody's left to play
+        }
         input = input.trim().to_string();
 
+        if input == "undo" || input == "redo" {
+            clear_term();
+            // Keep going until it's a human's turn again, or the bot would just replay its move
+            let (command, playing) = (input.clone(), game.seat_playing());
+            let mut result = if command == "undo" { game.undo() } else { game.redo() };
+            while result.is_ok() && bots[game.team_playing().0].is_some() {
+                result = if command == "undo" { game.undo() } else { game.redo() };
+            }
+            // It's someone else's turn now, their hand shouldn't be shown to whoever typed it
+            if privacy && game.seat_playing() != playing && !hand_off(&game, &mut input) {
+                break;
+            }
+            match result {
+                Ok(())  => println!("Done: {command}"),
+                Err(e)  => println!("{command} error: {e}"),
+            }
+            continue;
+        }
+        if input == "history" {
+            clear_term();
+            println!("This match so far:");
+            for (i, event) in game.curr_match.history.iter().enumerate() {
+                println!("{i:>3}. {event}");
+            }
+            println!();
+            continue;
+        }
+        if let Some(path) = input.strip_prefix("save ") {
+            clear_term();
+            match game.save(path.trim()) {
+                Ok(())  => println!("Game saved to '{}'", path.trim()),
+                Err(e)  => println!("save error: {e}"),
+            }
+            continue;
+        }
+        if let Some(path) = input.strip_prefix("load ") {
+            clear_term();
+            match Game::load(path.trim()) {
+                Ok(loaded) => {
+                    // The save may be for a different table, so the bots have to follow its teams
+                    (bots, privacy, no_humans) = seating(&loaded.rules);
+                    game = loaded;
+                    println!("Game loaded from '{}'", path.trim());
+                },
+                Err(e)     => println!("load error: {e}"),
+            }
+            continue;
+        }
+
         let move_made = match game.make_move(&input) {
             Ok(mov) => mov,
             Err(e) => {
                 clear_term();
-                println!("move error: {e:?}");
+                println!("move error: {e}");
                 continue;
             },
         };
 
         if let Some(tally) = game.is_match_over() {
-            clear_term();
-            let (purp_p, gren_p) = match game.who_is_first {
-                PlayerKind::Purple => (tally.first_points(), tally.shuf_points()),
-                PlayerKind::Green  => (tally.shuf_points(), tally.first_points()),
-            };
-            println!("Match over: Purple got '{purp_p}' points, Green got '{gren_p}'");
-            println!();
-            println!("The breakdown is:\n{}\n", tally);
-            game.purple_points += purp_p;
-            game.green_points  += gren_p;
-            println!("Updated running score is: {} '{}' - '{}' {}\n",
-                     purple_text(), game.purple_points,
-                     game.green_points, green_text());
-
-            // Full napoli takes preference over normal winner
-            if has_full_napoli(&game.curr_match.player_first.pile) {
-                println!("{} has achieved a full napoli: they win. What a nerd lmfao", game.who_is_first);
-                break;
-            }
-            else if has_full_napoli(&game.curr_match.player_shuffler.pile) {
-                println!("{} has achieved a full napoli: they win. What a nerd lmfao", !game.who_is_first);
+            if end_match(&mut game, tally) { break }
+
+            println!("Press any button to start the next match...");
+            stdin().read_line(&mut input).expect("Could not read from stdin");
+            // Someone else leads the new match
+            if privacy && !hand_off(&game, &mut input) {
                 break;
             }
-            else if let Some((player_name, win_p, lose_p)) = game.winner() {
-                println!("{player_name} has won with {win_p} points! The loser had {lose_p} points, what a nerd lmao");
+
+            continue;
+        } else {
+            game.next_turn();
+            if privacy && !hand_off(&game, &mut input) {
                 break;
             }
+        }
+        clear_term();
+        println!("The last player just played: '{}'\n\n", move_made.map(|m| m.to_string()).unwrap_or("No previous move".to_string()));
+    }
 
-            println!("Restarting match....");
-            game.toggle_whose_first();
-            game.curr_match = Match::new();
+    if let Some(path) = arg_value("--replay") {
+        match game.replay().save(&path) {
+            Ok(())  => println!("Replay saved to '{path}', watch it with `scopa-replay {path}`"),
+            Err(e)  => eprintln!("replay error: {e}"),
+        }
+    }
+}
 
-            println!("Press any button to start the next match...");
-            stdin().read_line(&mut input).expect("Could not read from stdin");
+/// Who's a bot, whether hands get hidden between turns, and whether anyone's a human at all
+fn seating(rules: &Rules) -> (Vec<Option<Box<dyn Strategy>>>, bool, bool) {
+    let bots = bots_from_args(rules);
+    let hot_seat  = bots.iter().all(Option::is_none);
+    // Hide the hand between hot seat turns, unless everyone's fine seeing each other's cards
+    let privacy   = hot_seat && !has_flag("--no-privacy");
+    let no_humans = bots.iter().all(Option::is_some);
+    (bots, privacy, no_humans)
+}
 
-            continue;
-        } else {
-            use std::{thread, time};
+/// Prints the results of the match that just ended and adds them to the running score.
+/// Returns whether the whole game is over, otherwise the next match gets dealt
+fn end_match(game: &mut Game, tally: PointTally) -> bool {
+    clear_term();
+    let points = game.award_points(&tally);
+    println!("Match over: {}", gained_text(&points));
+    println!();
+    println!("The breakdown is:\n{}\n", tally);
+    println!("Updated running score is: {}\n", score_text(&game.points));
 
-            clear_term();
+    // Full napoli takes preference over normal winner
+    if let Some(nerd) = game.full_napoli() {
+        println!("{nerd} has achieved a full napoli: they win. What a nerd lmfao");
+        return true;
+    }
+    else if let Some(winner) = game.winner() {
+        let others: Vec<String> = game.points.iter().enumerate()
+            .filter(|&(team, _)| team != winner.0)
+            .map(|(_, p)| p.to_string())
+            .collect();
+        let loser = if others.len() > 1 { "losers" } else { "loser" };
+        println!("{winner} has won with {} points! The {loser} had {} points, what a nerd lmao",
+                 game.points[winner.0], others.join(" and "));
+        return true;
+    }
+
+    println!("Restarting match....");
+    game.next_match();
+    false
+}
+
+/// `--connect <address>`: play on a `scopa-server` instead. It keeps the real game, we
+/// just show what it sends and pass our moves along
+fn play_online(address: &str) -> std::io::Result<()> {
+    let stream = TcpStream::connect(address)?;
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut input = String::new();
+    let mut rules = Rules::default();
+    println!("Connected to {address}, waiting for the other players...");
+
+    while let Some(msg) = read_message::<ServerMessage>(&mut reader)? {
+        match msg {
+            ServerMessage::Welcome { you, team, rules: r } => {
+                rules = r;
+                clear_term();
+                println!("You're playing {}\n", seat_text(you, team, &rules));
+            },
+            ServerMessage::State(view) => {
+                let others: Vec<String> = view.hand_lens.iter().enumerate()
+                    .filter(|&(seat, _)| seat != view.you)
+                    .map(|(seat, len)| format!("{} has '{len}'", seat_text(seat, rules.team_of(seat), &rules)))
+                    .collect();
+                let piles: Vec<String> = view.pile_lens.iter().zip(&view.scope).enumerate()
+                    .map(|(team, (len, scope))| match Team(team) == view.team {
+                        true  => format!("yours has '{len}' cards ({scope} scope)"),
+                        false => format!("{}'s '{len}' ({scope} scope)", Team(team)),
+                    })
+                    .collect();
+                println!("Score is: {}", score_text(&view.points));
+                println!("Deck has '{}' cards, {}", view.deck_len, others.join(", "));
+                println!("Piles: {}", piles.join(", "));
+                println!("Table has cards: '{}'", view.table.iter().enumerate().map(|(i, c)| format!("{c}({i})")).collect::<Vec<String>>().join(" ; "));
+                println!("You current cards are: {}", view.hand.iter().enumerate().map(|(i, c)| format!("{c}({i})")).collect::<Vec<String>>().join(" ; "));
 
-            println!("Waiting 1.5 seconds before switching...");
-            thread::sleep(time::Duration::from_millis(1500));
-            game.toggle_turn();
+                if view.playing != view.you {
+                    println!("Waiting for {} to play...\n", seat_text(view.playing, rules.team_of(view.playing), &rules));
+                    continue;
+                }
+                println!("Your turn! Waiting for input now....");
+                input.clear();
+                if stdin().read_line(&mut input)? == 0 {
+                    return Ok(()); // Hanging up lets the server tell the other player
+                }
+                write_message(&mut writer, &ClientMessage::Move { input: input.trim().to_string() })?;
+                clear_term();
+            },
+            ServerMessage::Played { mov } => println!("Just played: '{mov}'\n"),
+            ServerMessage::Rejected { reason } => println!("move error: {reason}\n"),
+            ServerMessage::MatchOver { tally, points } => {
+                println!("Match over: {}\n", gained_text(&points));
+                println!("The breakdown is:\n{tally}\n");
+            },
+            ServerMessage::GameOver { winner, points, seed } => {
+                println!("{winner} has won! Final score: {}", score_text(&points));
+                println!("Game seed: {seed} (pass `--seed {seed}` to play these deals again)");
+                break;
+            },
+            ServerMessage::OpponentLeft => {
+                println!("Somebody left, game over");
+                break;
+            },
         }
-        clear_term();
-        println!("The last player just played: '{}'\n\n", move_made.map(|m| m.to_string()).unwrap_or("No previous move".to_string()));
     }
+    Ok(())
+}
+
+/// Hides the screen until whoever plays next says they're the one looking at it.
+/// Returns false if stdin was closed instead
+fn hand_off(game: &Game, input: &mut String) -> bool {
+    clear_term();
+    println!("Pass to {}, and press Enter once nobody else can see the screen...", who_plays(game));
+    input.clear();
+    let read = stdin().read_line(input).expect("Could not read from stdin");
+    clear_term();
+    read > 0
+}
+
+/// The team playing, plus which of its two players it is when teams are pairs
+fn who_plays(game: &Game) -> String {
+    seat_text(game.seat_playing(), game.team_playing(), &game.rules)
+}
+
+/// `seat`'s team, in color, plus which of its two players it is when teams are pairs
+fn seat_text(seat: usize, team: Team, rules: &Rules) -> String {
+    match rules.players > rules.teams {
+        true  => format!("{team} ({})", seat_name(seat, team)),
+        false => team.to_string(),
+    }
+}
+
+/// Running score, as in `Purple '3' - Green '5'`
+fn score_text(points: &[usize]) -> String {
+    points.iter().enumerate()
+        .map(|(team, p)| format!("{} '{p}'", Team(team)))
+        .collect::<Vec<String>>()
+        .join(" - ")
+}
+
+/// What each team got out of a match, as in `Purple got '3' points, Green got '1'`
+fn gained_text(points: &[usize]) -> String {
+    points.iter().enumerate()
+        .map(|(team, p)| match team {
+            0 => format!("{} got '{p}' points", Team(team).name()),
+            _ => format!("{} got '{p}'", Team(team).name()),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 fn clear_term() {
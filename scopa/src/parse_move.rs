@@ -1,42 +1,223 @@
 use nom::{
     IResult,
-    bytes::complete::tag,
+    bytes::complete::{tag, tag_no_case},
+    character::complete::{multispace0, multispace1},
+    combinator::{map, opt, value, verify},
     multi::separated_list0,
+    sequence::{delimited, pair},
     branch::alt,
 };
 
-use crate::ParsedMove;
+use core::*;
+use crate::{captures_with, AceOnEmptyTable, Action, MoveError, PlayerView};
 
+/// A card as it was written in a move: where it is, or what it is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardRef {
+    Index(usize),
+    Named(Card),
+}
+
+/// A move as it was typed, before looking its cards up (see [`ParsedMove::resolve`])
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsedMove {
+    Drop { hand: CardRef },
+    Capture { hand: CardRef, table: Vec<CardRef> },
+    /// Just the hand card: whatever it can take, as long as there's exactly one way to do it
+    Auto { hand: CardRef },
+}
+
+impl ParsedMove {
+    /// Turns the named cards into indices of the hand and table in `view`, and works out
+    /// what an [`ParsedMove::Auto`] takes
+    pub fn resolve(self, view: &PlayerView) -> Result<Action, MoveError> {
+        let (hand, table) = (view.hand, view.table);
+        let in_hand = |r: CardRef| match r {
+            CardRef::Index(i)    => Ok(i),
+            CardRef::Named(card) => hand.iter().position(|&c| c == card).ok_or(MoveError::NotInHand(card)),
+        };
+        let on_table = |r: CardRef| match r {
+            CardRef::Index(i)    => Ok(i),
+            CardRef::Named(card) => table.iter().position(|&c| c == card).ok_or(MoveError::NotOnTable(card)),
+        };
+
+        match self {
+            ParsedMove::Drop { hand }           => Ok(Action::Drop { hand: in_hand(hand)? }),
+            ParsedMove::Capture { hand, table } => Ok(Action::Capture {
+                hand: in_hand(hand)?,
+                table: table.into_iter().map(on_table).collect::<Result<_, _>>()?,
+            }),
+            ParsedMove::Auto { hand }           => {
+                let i = in_hand(hand)?;
+                let card = *view.hand.get(i).ok_or(MoveError::OutOfRangeOfHand { index: i, hand_len: view.hand.len() })?;
+                // An ace that stays on an empty table isn't taking anything either
+                if view.rules.ace_sweeps(card) && table.is_empty() && view.rules.ace_on_empty_table == AceOnEmptyTable::Stays {
+                    return Err(MoveError::NothingToTake(card));
+                }
+                let mut captures = captures_with(card, table, &view.rules);
+                match captures.len() {
+                    0 => Err(MoveError::NothingToTake(card)),
+                    1 => Ok(Action::Capture { hand: i, table: captures.remove(0) }),
+                    _ => Err(MoveError::Ambiguous {
+                        card,
+                        options: captures.into_iter().map(|t| Action::Capture { hand: i, table: t }).collect(),
+                    }),
+                }
+            },
+        }
+    }
+}
 
 pub fn parse_move_internal(input: &str) -> IResult<&str, ParsedMove> {
-    alt((parse_table_drop_move, parse_taking_move))(input)
+    alt((parse_table_drop_move, parse_taking_move, parse_auto_move))(input)
 }
 
+/// `t0`, `t0;`, `t7D` or `drop re bastoni`
 pub fn parse_table_drop_move(input: &str) -> IResult<&str, ParsedMove> {
-    let (input, _) = tag("t")(input)?;
-    let (input, left) = parse_left(input)?;
+    let (input, _) = alt((pair(tag_no_case("drop"), multispace1), pair(tag_no_case("t"), multispace0)))(input)?;
+    let (input, left) = parse_card_ref(input)?;
+    let (input, _) = opt(pair(multispace0, tag(";")))(input)?;
 
-    Ok((input, ParsedMove {
-        from: left as usize,
-        to: None,
+    Ok((input, ParsedMove::Drop {
+        hand: left,
     }))
 }
 
+/// `0;1+2`, `7D;3C+4S` or `7 denari takes 3 coppe + 4 spade`
 pub fn parse_taking_move(input: &str) -> IResult<&str, ParsedMove> {
-    let (input, left) = parse_left(input)?;
-    let (input, _) = tag(";")(input)?;
+    let (input, left) = parse_card_ref(input)?;
+    let (input, _) = delimited(multispace0, alt((tag(";"), tag_no_case("takes"), tag_no_case("take"))), multispace0)(input)?;
     let (input, right) = parse_right(input)?;
 
-    Ok((input, ParsedMove {
-        from: left as usize,
-        to: Some(right.into_iter().map(|n| n as usize).collect())
+    Ok((input, ParsedMove::Capture {
+        hand: left,
+        table: right,
     }))
 }
-fn parse_left(input: &str) -> IResult<&str, u32> {
-    nom::character::complete::u32(input)
+
+/// `2` or `7D`, see [`ParsedMove::Auto`]
+pub fn parse_auto_move(input: &str) -> IResult<&str, ParsedMove> {
+    map(parse_card_ref, |hand| ParsedMove::Auto { hand })(input)
+}
+
+fn parse_right(input: &str) -> IResult<&str, Vec<CardRef>> {
+    separated_list0(delimited(multispace0, tag("+"), multispace0), parse_card_ref)(input)
+}
+
+/// A card name if there is one, otherwise an index
+fn parse_card_ref(input: &str) -> IResult<&str, CardRef> {
+    alt((
+        map(parse_card, CardRef::Named),
+        map(nom::character::complete::u32, |n| CardRef::Index(n as usize)),
+    ))(input)
+}
+
+/// Rank then suit, either as the short code (`7D`, `RB`) or in words (`7 denari`, `re bastoni`)
+fn parse_card(input: &str) -> IResult<&str, Card> {
+    let (input, rank) = parse_rank(input)?;
+    let (input, _) = multispace0(input)?;
+    let (input, suit) = parse_suit(input)?;
+    Ok((input, Card::new(suit, rank)))
+}
+
+fn parse_rank(input: &str) -> IResult<&str, usize> {
+    // Whole words go first, or `asso` would be read as the `A` of `AS`
+    alt((
+        value(1,  alt((tag_no_case("asso"), tag_no_case("ace")))),
+        value(8,  alt((tag_no_case("fante"), tag_no_case("jack")))),
+        value(9,  alt((tag_no_case("cavallo"), tag_no_case("knight")))),
+        value(10, alt((tag_no_case("re"), tag_no_case("king")))),
+        value(1,  tag_no_case("a")),
+        value(8,  tag_no_case("f")),
+        value(9,  tag_no_case("c")),
+        value(10, tag_no_case("r")),
+        map(verify(nom::character::complete::u32, |n| (1..=10).contains(n)), |n| n as usize),
+    ))(input)
 }
 
-fn parse_right(input: &str) -> IResult<&str, Vec<u32>> {
-    separated_list0(tag("+"), nom::character::complete::u32)(input)
+fn parse_suit(input: &str) -> IResult<&str, Suit> {
+    alt((
+        value(Suit::Denari,  alt((tag_no_case("denari"), tag_no_case("denaro"), tag_no_case("d")))),
+        value(Suit::Coppe,   alt((tag_no_case("coppe"), tag_no_case("coppa"), tag_no_case("c")))),
+        value(Suit::Bastoni, alt((tag_no_case("bastoni"), tag_no_case("bastone"), tag_no_case("b")))),
+        value(Suit::Spade,   alt((tag_no_case("spade"), tag_no_case("spada"), tag_no_case("s")))),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use nom::combinator::all_consuming;
+    use CardRef::{Index, Named};
+
+    fn parse(input: &str) -> ParsedMove {
+        all_consuming(parse_move_internal)(input).expect("The README says this is a move").1
+    }
+
+    fn capture(hand: CardRef, table: &[CardRef]) -> ParsedMove {
+        ParsedMove::Capture { hand, table: table.to_vec() }
+    }
+
+    #[test]
+    fn index_notation() {
+        assert_eq!(parse("0;1"),     capture(Index(0), &[Index(1)]));
+        assert_eq!(parse("0;1+2"),   capture(Index(0), &[Index(1), Index(2)]));
+        assert_eq!(parse("0;1+2+3"), capture(Index(0), &[Index(1), Index(2), Index(3)]));
+        assert_eq!(parse("0;"),      capture(Index(0), &[]));
+        assert_eq!(parse("t0;"),     ParsedMove::Drop { hand: Index(0) });
+        assert_eq!(parse("t0"),      ParsedMove::Drop { hand: Index(0) });
+    }
+
+    #[test]
+    fn named_notation() {
+        let (seven_d, three_c, four_s) = (Card::new(Suit::Denari, 7), Card::new(Suit::Coppe, 3), Card::new(Suit::Spade, 4));
+        assert_eq!(parse("7D;3C+4S"), capture(Named(seven_d), &[Named(three_c), Named(four_s)]));
+        assert_eq!(parse("tRB"),      ParsedMove::Drop { hand: Named(Card::new(Suit::Bastoni, 10)) });
+        assert_eq!(parse("7 denari takes 3 coppe + 4 spade"), capture(Named(seven_d), &[Named(three_c), Named(four_s)]));
+        assert_eq!(parse("drop re bastoni"),  ParsedMove::Drop { hand: Named(Card::new(Suit::Bastoni, 10)) });
+        assert_eq!(parse("asso spade takes"), capture(Named(Card::new(Suit::Spade, 1)), &[]));
+        assert_eq!(parse("7D;0+4S"),  capture(Named(seven_d), &[Index(0), Named(four_s)]));
+        assert_eq!(parse("7d;3c+4s"), capture(Named(seven_d), &[Named(three_c), Named(four_s)]));
+    }
+
+    fn auto(hand: &[Card], table: &[Card], rules: Rules) -> Result<Action, MoveError> {
+        let table: Deck = table.iter().copied().collect();
+        let view = PlayerView {
+            seat: 0,
+            team: rules.team_of(0),
+            hand,
+            table: &table,
+            piles: vec![Deck::default(); 2],
+            scope: vec![0; 2],
+            hand_lens: vec![hand.len(); 2],
+            shown: vec![vec![]; 2],
+            deck_len: 0,
+            last_taker: None,
+            rules,
+        };
+        ParsedMove::Auto { hand: Index(0) }.resolve(&view)
+    }
+
+    #[test]
+    fn shorthand_takes_the_only_capture() {
+        let (seven, three, four, five) = (Card::denari(7), Card::new(Suit::Coppe, 3), Card::new(Suit::Spade, 4), Card::new(Suit::Coppe, 5));
+        assert_eq!(auto(&[seven], &[three, four, five], Rules::classic()), Ok(Action::Capture { hand: 0, table: vec![0, 1] }));
+        assert!(matches!(auto(&[seven], &[three, four, five, Card::new(Suit::Spade, 2)], Rules::classic()), Err(MoveError::Ambiguous { .. })));
+        // Not a drop in disguise, that's `t`
+        assert_eq!(auto(&[seven], &[five], Rules::classic()), Err(MoveError::NothingToTake(seven)));
+
+        // An ace sweeping a table, or staying on an empty one
+        let ace = Card::denari(1);
+        assert_eq!(auto(&[ace], &[five], Rules::default()), Ok(Action::Capture { hand: 0, table: vec![] }));
+        assert_eq!(auto(&[ace], &[], Rules::default()), Err(MoveError::NothingToTake(ace)));
+        let taken = Rules { ace_on_empty_table: AceOnEmptyTable::Taken, ..Rules::default() };
+        assert_eq!(auto(&[ace], &[], taken), Ok(Action::Capture { hand: 0, table: vec![] }));
+    }
 
+    #[test]
+    fn shorthand_notation() {
+        assert_eq!(parse("2"),  ParsedMove::Auto { hand: Index(2) });
+        assert_eq!(parse("7D"), ParsedMove::Auto { hand: Named(Card::new(Suit::Denari, 7)) });
+    }
 }
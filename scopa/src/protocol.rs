@@ -0,0 +1,92 @@
+use std::io::{BufRead, Write};
+
+use core::*;
+use crate::{Game, Move, PointTally, Rules, Team};
+
+/// What clients send to `scopa-server`, one json object per line
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// A move, written like in the REPL (see the README)
+    Move { input: String },
+}
+
+/// What `scopa-server` sends its clients, one json object per line
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// First thing after connecting: your seat, its team and what's being played. No seed:
+    /// it decides every deal, so whoever has it knows everyone's hand
+    Welcome { you: usize, team: Team, rules: Rules },
+    /// How the game looks from your seat, sent before every move
+    State(SeatView),
+    /// `mov` was made (the seat and team that made it are in there)
+    Played { mov: Move },
+    /// Your move wasn't valid (`reason` says why), it's still your turn
+    Rejected { reason: String },
+    /// The match is over: its breakdown and the points each team got out of it
+    MatchOver { tally: PointTally, points: Vec<usize> },
+    /// Someone reached the target score (or got a full napoli). `points` are the totals, by
+    /// team, and `seed` can be used to play the same deals again now that they're all over
+    GameOver { winner: Team, points: Vec<usize>, seed: u64 },
+    /// Somebody else disconnected, the game can't go on
+    OpponentLeft,
+}
+
+/// A [`crate::PlayerView`] that can be sent over the wire: your hand, the table, and
+/// only how many cards everyone else has
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SeatView {
+    pub you: usize,
+    pub team: Team,
+    /// Whose turn it is
+    pub playing: usize,
+    pub hand: Vec<Card>,
+    pub table: Deck,
+    /// How many cards each team has taken
+    pub pile_lens: Vec<usize>,
+    /// Each team's scope
+    pub scope: Vec<usize>,
+    /// How many cards each seat holds, yours included
+    pub hand_lens: Vec<usize>,
+    pub deck_len: usize,
+    /// Each team's score
+    pub points: Vec<usize>,
+}
+
+impl SeatView {
+    /// What seat `you` can see of `game`
+    pub fn of(game: &Game, you: usize) -> SeatView {
+        let view = game.view_of(you);
+        SeatView {
+            you,
+            team: view.team,
+            playing: game.seat_playing(),
+            hand: view.hand.to_vec(),
+            table: view.table.clone(),
+            pile_lens: view.piles.iter().map(Deck::len).collect(),
+            scope: view.scope.clone(),
+            hand_lens: view.hand_lens.clone(),
+            deck_len: view.deck_len,
+            points: game.points.clone(),
+        }
+    }
+}
+
+/// Writes `msg` as a line of json
+pub fn write_message<T: serde::Serialize>(w: &mut impl Write, msg: &T) -> std::io::Result<()> {
+    let json = serde_json::to_string(msg).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")?;
+    w.flush()
+}
+
+/// Reads a line of json, `None` once the other end hangs up
+pub fn read_message<T: serde::de::DeserializeOwned>(r: &mut impl BufRead) -> std::io::Result<Option<T>> {
+    let mut line = String::new();
+    if r.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
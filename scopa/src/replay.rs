@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{Game, Move, PointTally, Rules};
+
+/// A whole game written down: the seed decides every deal, so the moves are all that's
+/// needed to play it again exactly as it went. Get one from [`Game::replay`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules,
+    /// Moves of each match, in the order they were made
+    pub matches: Vec<Vec<Move>>,
+}
+
+/// How the game looked at one point of a [`Replay`]
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub game: Game,
+    /// Move that led here, `None` right after a deal
+    pub mov: Option<Move>,
+    /// Set on the last move of a match, which already has its points added to the score
+    pub tally: Option<PointTally>,
+}
+
+/// A move in the replay that can't be made in the position it's supposed to be made in
+#[derive(Clone, Debug)]
+pub struct ReplayError {
+    pub match_index: usize,
+    pub move_index: usize,
+    pub mov: Move,
+}
+
+impl Replay {
+    /// Plays the replay from the start: one frame per deal plus one per move. There's always
+    /// at least the first deal, even if no match got recorded
+    pub fn frames(&self) -> Result<Vec<Frame>, ReplayError> {
+        // Every frame owns a copy of the game, no need to also copy the undo snapshots
+        let mut game = Game::from_seed(self.seed, Rules { allow_undo: false, ..self.rules });
+        let mut frames = vec![];
+
+        let no_moves = [vec![]];
+        let matches = match self.matches.is_empty() {
+            true  => &no_moves[..],
+            false => &self.matches[..],
+        };
+        for (match_index, moves) in matches.iter().enumerate() {
+            if match_index > 0 { game.next_match() }
+            frames.push(Frame { game: game.clone(), mov: None, tally: None });
+
+            for (move_index, mov) in moves.iter().enumerate() {
+                let err = || ReplayError { match_index, move_index, mov: mov.clone() };
+                let action = game.curr_match.action_for(mov).ok_or_else(err)?;
+                game.play(&action).map_err(|_| err())?;
+
+                let tally = game.is_match_over();
+                match &tally {
+                    Some(tally) => { game.award_points(tally); },
+                    None        => game.next_turn(),
+                }
+                frames.push(Frame { game: game.clone(), mov: Some(mov.clone()), tally });
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Writes the replay to `path` as json
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), crate::SaveError> {
+        let json = serde_json::to_string_pretty(self).map_err(crate::SaveError::Format)?;
+        std::fs::write(path, json).map_err(crate::SaveError::Io)
+    }
+
+    /// Reads back a replay written by [`Replay::save`]
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Replay, crate::SaveError> {
+        let json = std::fs::read_to_string(path).map_err(crate::SaveError::Io)?;
+        serde_json::from_str(&json).map_err(crate::SaveError::Format)
+    }
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "move {} of match {} ({}) can't be played there",
+               self.move_index + 1, self.match_index + 1, self.mov)
+    }
+}
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_start_with_the_first_deal() {
+        let empty = Replay { seed: 1, rules: Rules::default(), matches: vec![] };
+        let frames = empty.frames().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].mov.is_none());
+
+        let mut game = Game::from_seed(1, Rules::default());
+        let action = game.view().legal_moves().remove(0);
+        game.play(&action).unwrap();
+        let frames = game.replay().frames().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].game.curr_match.table.len(), 4);
+    }
+}
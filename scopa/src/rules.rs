@@ -0,0 +1,217 @@
+use core::*;
+use crate::Team;
+
+/// Which rules a game is played with. [`Rules::default`] is what this crate has always
+/// played (asso piglia tutto, re bello and napoli, first to 21, no capture priority or must
+/// capture), the other constructors are presets for common variants. Every field can be
+/// changed for house rules
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Rules {
+    /// Points needed to win the game
+    pub target_score: usize,
+    /// An ace takes every card on the table, whether it's written as a capture (`a;`) or a
+    /// drop (`ta`)
+    pub asso_piglia_tutto: bool,
+    /// With asso piglia tutto, sweeping the table with an ace counts as a scopa
+    pub ace_sweep_scopa: bool,
+    /// With asso piglia tutto, what an ace played on an empty table does
+    pub ace_on_empty_table: AceOnEmptyTable,
+    /// The re di denari is worth a point, like the sette bello
+    pub re_bello: bool,
+    /// The ace, 2 and 3 of denari are worth a point, plus one per denari card following them
+    pub napoli: bool,
+    /// Taking every denari card wins the whole game on the spot
+    pub full_napoli_wins: bool,
+    /// Cirulla's grande (fante, cavallo and re of denari, worth 5) and piccola (ace, 2 and 3
+    /// of denari, worth 3 plus one per denari card following them, up to the 6)
+    pub grande_piccola: bool,
+    /// Clearing the table on the very last play of a match counts as a scopa
+    pub last_play_scopa: bool,
+    /// When a table card has the same value as the card played, that card is the one that
+    /// gets taken: no `7;3+4` while there's a 7 on the table
+    pub capture_priority: bool,
+    /// A card that could take something can't be placed on the table instead
+    pub must_capture: bool,
+    /// A card can also take any table cards that add up to 15 together with it, as in Cirulla
+    pub capture_fifteen: bool,
+    /// When the starting table adds up to 15 the shuffler takes it and scores a scopa, two
+    /// scope if it adds up to 30
+    pub table_bonus: bool,
+    /// A fresh hand of three adding up to less than 10 gets shown for 3 scope (bussare), three
+    /// of the same value for 10 (decino). Nobody turns free points down, so it happens on its own
+    pub declarations: bool,
+    /// The 7 of coppe is wild for [`Rules::table_bonus`] and [`Rules::declarations`], worth
+    /// whatever helps most. When played it's just a 7
+    pub matta: bool,
+    /// Moves can be taken back with [`crate::Match::undo`]. Turn it off for competitive play
+    pub allow_undo: bool,
+    /// People at the table: 2, 3 or 4 (scopone), or 6
+    pub players: usize,
+    /// Teams the players are split into, with seat `s` playing for team `s % teams` (see
+    /// [`Rules::team_of`]). Partners add up their cards and scope. As many as `players` when
+    /// everyone plays for themselves
+    pub teams: usize,
+    /// Cards dealt to each player at a time, they get dealt again whenever they run out
+    pub hand_size: usize,
+    /// Cards dealt face up on the table at the start of a match
+    pub table_cards: usize,
+}
+
+impl Rules {
+    /// Plain scopa: scope, cards, denari, sette bello and primiera, first to 11
+    pub fn classic() -> Rules {
+        Rules {
+            target_score: 11,
+            asso_piglia_tutto: false,
+            ace_sweep_scopa: false,
+            ace_on_empty_table: AceOnEmptyTable::Stays,
+            re_bello: false,
+            napoli: false,
+            full_napoli_wins: false,
+            grande_piccola: false,
+            last_play_scopa: false,
+            capture_priority: true,
+            must_capture: true,
+            capture_fifteen: false,
+            table_bonus: false,
+            declarations: false,
+            matta: false,
+            allow_undo: true,
+            players: 2,
+            teams: 2,
+            hand_size: 3,
+            table_cards: 4,
+        }
+    }
+
+    /// Scopa d'Assi (also just "asso piglia tutto"): classic, but aces sweep the table
+    pub fn scopa_d_assi() -> Rules {
+        Rules { asso_piglia_tutto: true, ..Rules::classic() }
+    }
+
+    /// Scopa napoletana: classic plus the napoli (and its instant win), first to 21
+    pub fn napoletana() -> Rules {
+        Rules { napoli: true, full_napoli_wins: true, target_score: 21, ..Rules::classic() }
+    }
+
+    /// Scopone: classic scoring for two teams of two. The whole deck gets dealt at once,
+    /// 9 cards each and 4 on the table
+    pub fn scopone() -> Rules {
+        Rules { players: 4, hand_size: 9, table_cards: 4, ..Rules::classic() }
+    }
+
+    /// Scopone scientifico: scopone with 10 cards each and nothing on the table to begin with
+    pub fn scopone_scientifico() -> Rules {
+        Rules { hand_size: 10, table_cards: 0, ..Rules::scopone() }
+    }
+
+    /// Classic scopa for three, each playing for themselves
+    pub fn three_players() -> Rules {
+        Rules { players: 3, teams: 3, ..Rules::classic() }
+    }
+
+    /// Classic scopa for six, in three teams of two
+    pub fn six_players() -> Rules {
+        Rules { players: 6, teams: 3, ..Rules::classic() }
+    }
+
+    /// Cirulla, from Genoa: asso piglia tutto, captures adding up to 15, the matta, bonuses
+    /// for the starting table and for hands, grande and piccola, first to 51
+    pub fn cirulla() -> Rules {
+        Rules {
+            target_score: 51,
+            asso_piglia_tutto: true,
+            grande_piccola: true,
+            capture_fifteen: true,
+            table_bonus: true,
+            declarations: true,
+            matta: true,
+            ..Rules::classic()
+        }
+    }
+
+    /// Same rules, but no taking moves back
+    pub fn competitive(self) -> Rules {
+        Rules { allow_undo: false, ..self }
+    }
+
+    /// Names [`Rules::preset`] knows, leaving out the longer spellings
+    pub const PRESETS: &'static [&'static str] = &[
+        "classic", "assi", "napoletana", "scopone", "scientifico", "three", "six", "cirulla", "default",
+    ];
+
+    /// Looks a preset up by name, one of [`Rules::PRESETS`] (`scopa-d-assi` and
+    /// `scopone-scientifico` work too)
+    pub fn preset(name: &str) -> Option<Rules> {
+        match name.to_ascii_lowercase().as_str() {
+            "classic"                             => Some(Rules::classic()),
+            "assi" | "scopa-d-assi"               => Some(Rules::scopa_d_assi()),
+            "napoletana"                          => Some(Rules::napoletana()),
+            "scopone"                             => Some(Rules::scopone()),
+            "scientifico" | "scopone-scientifico" => Some(Rules::scopone_scientifico()),
+            "three"                               => Some(Rules::three_players()),
+            "six"                                 => Some(Rules::six_players()),
+            "cirulla"                             => Some(Rules::cirulla()),
+            "default"                             => Some(Rules::default()),
+            _                                     => None,
+        }
+    }
+
+    /// Whether playing `card` sweeps the table, see [`Rules::asso_piglia_tutto`]
+    pub fn ace_sweeps(&self, card: Card) -> bool {
+        self.asso_piglia_tutto && card.number == CardNum::Numeric(1)
+    }
+
+    /// Whether `card` is the matta, see [`Rules::matta`]
+    pub fn is_matta(&self, card: Card) -> bool {
+        self.matta && card == Card::new(Suit::Coppe, 7)
+    }
+
+    /// Which team `seat` plays for
+    pub fn team_of(&self, seat: usize) -> Team {
+        Team(seat % self.teams)
+    }
+
+    /// Every team, in order
+    pub fn all_teams(&self) -> impl Iterator<Item = Team> {
+        (0..self.teams).map(Team)
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            target_score: 21,
+            asso_piglia_tutto: true,
+            ace_sweep_scopa: false,
+            ace_on_empty_table: AceOnEmptyTable::Stays,
+            re_bello: true,
+            napoli: true,
+            full_napoli_wins: true,
+            grande_piccola: false,
+            last_play_scopa: true,
+            capture_priority: false,
+            must_capture: false,
+            capture_fifteen: false,
+            table_bonus: false,
+            declarations: false,
+            matta: false,
+            allow_undo: true,
+            players: 2,
+            teams: 2,
+            hand_size: 3,
+            table_cards: 4,
+        }
+    }
+}
+
+/// What an ace does when there's nothing on the table to sweep
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AceOnEmptyTable {
+    /// It gets placed on the table, like any other card
+    Stays,
+    /// It takes itself: straight to the player's pile, and they count as having taken last
+    Taken,
+}
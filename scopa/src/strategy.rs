@@ -0,0 +1,114 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use core::*;
+use crate::{Action, Difficulty, IsmctsBot, PlayerView};
+
+/// Something that can pick moves: a bot
+pub trait Strategy {
+    /// Picks a move for the player whose view this is. Should always return a move that
+    /// [`crate::Match::play`] accepts
+    fn choose(&mut self, view: &PlayerView) -> Action;
+}
+
+/// Picks a player by name: `human` (`None`, nobody picks for them), `greedy`, `random` or
+/// `ismcts` (optionally `ismcts:<difficulty>` or `ismcts:<iterations>`)
+pub fn strategy_from_name(name: &str) -> Result<Option<Box<dyn Strategy>>, String> {
+    match name {
+        "human"  => Ok(None),
+        "greedy" => Ok(Some(Box::new(GreedyBot))),
+        "random" => Ok(Some(Box::new(RandomBot::new()))),
+        "ismcts" => Ok(Some(Box::new(IsmctsBot::with_difficulty(Difficulty::Medium)))),
+        other    => match other.strip_prefix("ismcts:") {
+            Some(level) => match (level.parse::<usize>(), level.parse::<Difficulty>()) {
+                (Ok(iterations), _) => Ok(Some(Box::new(IsmctsBot::new(iterations)))),
+                (_, Ok(difficulty)) => Ok(Some(Box::new(IsmctsBot::with_difficulty(difficulty)))),
+                (_, Err(e))         => Err(e),
+            },
+            None => Err(format!("'{other}' isn't a player: pick one of human, greedy, random or ismcts[:difficulty]")),
+        },
+    }
+}
+
+/// Plays any move it can, no thinking involved
+#[derive(Clone, Debug)]
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+/// Grabs as much as it can right now: scope first, then the sette bello, denari and
+/// primiera cards. When it can't take anything it drops the card that gives away the least
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyBot;
+
+impl RandomBot {
+    pub fn new() -> RandomBot {
+        RandomBot { rng: StdRng::from_entropy() }
+    }
+    pub fn from_seed(seed: u64) -> RandomBot {
+        RandomBot { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Default for RandomBot {
+    fn default() -> Self { Self::new() }
+}
+
+impl Strategy for RandomBot {
+    fn choose(&mut self, view: &PlayerView) -> Action {
+        let actions = view.legal_moves();
+        actions.choose(&mut self.rng).expect("Asked for a move with an empty hand").clone()
+    }
+}
+
+impl Strategy for GreedyBot {
+    fn choose(&mut self, view: &PlayerView) -> Action {
+        view.legal_moves().into_iter()
+            .max_by_key(|a| greedy_score(view, a))
+            .expect("Asked for a move with an empty hand")
+    }
+}
+
+/// How good `action` looks if you only care about what it gets you this turn
+fn greedy_score(view: &PlayerView, action: &Action) -> i32 {
+    let card = view.hand[action.hand_index()];
+    match action {
+        Action::Capture { table, .. } => {
+            let is_ace = view.rules.ace_sweeps(card);
+            let taken: Vec<Card> = if is_ace {
+                view.table.iter().copied().collect()
+            } else {
+                table.iter().map(|&i| view.table[i]).collect()
+            };
+            let scopa = !taken.is_empty() && taken.len() == view.table.len() && (!is_ace || view.rules.ace_sweep_scopa);
+
+            100 + card_worth(&card) + taken.iter().map(card_worth).sum::<i32>() + if scopa { 50 } else { 0 }
+        },
+        Action::Drop { .. } => {
+            // Leaving little on the table is an invitation for the opponent to scopa us
+            let table_sum: usize = view.table.iter().map(|c| c.value()).sum::<usize>() + card.value();
+            let scopa_risk = if table_sum <= 10 { 20 } else { 0 };
+
+            -card_worth(&card) - scopa_risk
+        },
+    }
+}
+
+/// Rough value of owning a card at the end of the match
+fn card_worth(card: &Card) -> i32 {
+    let mut worth = 1; // Every card helps with the number of cards
+
+    if card.suit == Suit::Denari { worth += 2 }
+    if *card == Card::denari(7)  { worth += 20 }
+    if *card == Card::denari(10) { worth += 5 }
+
+    // Primiera cares most about 7s, then 6s and aces
+    worth += match card.value() {
+        7 => 4,
+        6 => 2,
+        1 => 1,
+        _ => 0,
+    };
+    worth
+}